@@ -0,0 +1,316 @@
+mod tui;
+
+use psolo_core::board::{Board, Move};
+use psolo_core::record::Record;
+use psolo_core::solver::Outcome;
+use psolo_core::{boards, notation, serial, solver, svg};
+use std::io::{self, BufRead, Write};
+use std::time::Duration;
+
+const USAGE: &str = "Usage: psolo-cli <command> [arguments]
+
+Commands:
+    play <board>      Play in the terminal, typing moves such as d2-d4
+    tui <board>       Play in a full-screen terminal interface
+    solve <board>     Find a way to leave a single peg on the board
+    check <record>    Replay a game record and tell whether it is valid
+    census <board>    Count the positions reachable from the board
+    fools <board>     Find the quickest way to get stuck
+    list-boards       List the boards shipped with the game
+    diff <board> <board>
+                      Show the cells that differ between two positions:
+                      + for a peg that appeared, - for a peg that went
+    convert <record> <output>
+                      Write a game record in another format, told by the
+                      extension of <output>: .json, .bin (compact binary)
+                      or anything else for text
+    svg <board>       Print a drawing of the board as an SVG image
+    svg-solution <board> [grid|arrows]
+                      Print a solution as an SVG image: a grid of numbered
+                      diagrams (the default) or a single diagram with an
+                      arrow for each jump
+    animate <record>  Print an animated SVG image of the moves of a game
+    animate-solution <board>
+                      Print an animated SVG image of a solution
+
+<board> is the name of a shipped board or the path to a file holding its
+shape, written with X (peg), O (hole) and spaces (unusable cell).
+<record> is a game record in text, JSON (.json) or binary (.bin) form.";
+
+fn load_board(name_or_path: &str) -> Result<Board, String> {
+    let shape = if boards::shape_by_name(name_or_path).is_some() {
+        name_or_path.to_string()
+    } else {
+        std::fs::read_to_string(name_or_path)
+            .map_err(|e| format!("Unable to read \"{}\": {}", name_or_path, e))?
+    };
+    boards::load(&shape).map_err(|e| format!("\"{}\" is not a valid board: {}", name_or_path, e))
+}
+
+fn print_board(board: &Board) {
+    println!("{:#}", board);
+}
+
+fn play(board: Board) -> Result<(), String> {
+    let mut record = Record::new(board.clone());
+    let mut board = board;
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    println!("Type a move such as d2-d4, [u] to undo, [r] to restart or [q] to quit.");
+    loop {
+        println!();
+        print_board(&board);
+        let n_peg_left = board.count_peg();
+        if n_peg_left == 1 {
+            println!("You win !!");
+            break;
+        } else if !board.has_valid_move_left() {
+            println!("No valid move left ! ({} pieces left)", n_peg_left);
+            break;
+        }
+        print!("{} pieces left > ", n_peg_left);
+        io::stdout().flush().map_err(|e| e.to_string())?;
+
+        let line = match lines.next() {
+            Some(line) => line.map_err(|e| e.to_string())?,
+            None => break,
+        };
+        match line.trim() {
+            "q" => break,
+            "u" => match record.moves.pop() {
+                Some((src, dest)) => board.undo_move(src, dest),
+                None => println!("Nothing to undo."),
+            },
+            "r" => {
+                board = record.start.clone();
+                record.moves.clear();
+            }
+            input => match notation::parse_move(input) {
+                Ok((src, dest)) if board.is_valid_move(src, dest) => {
+                    board.make_move(src, dest);
+                    record.moves.push((src, dest));
+                }
+                Ok(_) => println!("{} is not a valid move.", input),
+                Err(_) => println!("Unable to read \"{}\" as a move.", input),
+            },
+        }
+    }
+
+    if !record.moves.is_empty() {
+        let moves: Vec<String> = record
+            .moves
+            .iter()
+            .map(|&mv| notation::format_move(mv))
+            .collect();
+        println!("Moves played: {}", moves.join(" "));
+    }
+    Ok(())
+}
+
+fn find_solution(board: &Board) -> Result<Vec<Move>, String> {
+    match solver::solve(board).map_err(|e| format!("Unable to solve the board: {}", e))? {
+        Outcome::Solved(moves) => Ok(moves),
+        Outcome::Unsolvable => Err("This board can't be reduced to a single peg".to_string()),
+        Outcome::GaveUp => Err(format!(
+            "No solution found after ruling out {} positions",
+            solver::SEARCH_LIMIT
+        )),
+    }
+}
+
+fn solve(board: Board) -> Result<(), String> {
+    let moves = find_solution(&board)?;
+    for (n, &mv) in moves.iter().enumerate() {
+        println!("{:>3}. {}", n + 1, notation::format_move(mv));
+    }
+    Ok(())
+}
+
+fn check(path: &str) -> Result<(), String> {
+    let record = read_record(path)?;
+    match record.replay() {
+        Ok(board) => {
+            println!(
+                "Valid game: {} moves played, {} pieces left{}{}",
+                record.moves.len(),
+                board.count_peg(),
+                if board.count_peg() == 1 {
+                    " (solved)"
+                } else {
+                    ""
+                },
+                match record.elapsed {
+                    Some(elapsed) => format!(", in {} s", elapsed.as_secs()),
+                    None => String::new(),
+                }
+            );
+            Ok(())
+        }
+        Err(index) => Err(format!(
+            "Move {} ({}) is not valid",
+            index + 1,
+            notation::format_move(record.moves[index])
+        )),
+    }
+}
+
+fn census(board: Board) -> Result<(), String> {
+    let levels =
+        solver::census(&board).map_err(|e| format!("Unable to enumerate the positions: {}", e))?;
+    println!("{:>5} {:>12} {:>12}", "pegs", "positions", "winnable");
+    for level in &levels {
+        println!(
+            "{:>5} {:>12} {:>12}",
+            level.pegs, level.positions, level.winnable
+        );
+    }
+    println!(
+        "total {:>12} {:>12}",
+        levels.iter().map(|l| l.positions).sum::<usize>(),
+        levels.iter().map(|l| l.winnable).sum::<usize>()
+    );
+    Ok(())
+}
+
+fn fools(mut board: Board) -> Result<(), String> {
+    let moves = solver::fools_solitaire(&board)
+        .map_err(|e| format!("Unable to search the board: {}", e))?;
+    for (n, &(src, dest)) in moves.iter().enumerate() {
+        println!("{:>3}. {}", n + 1, notation::format_move((src, dest)));
+        board.make_move(src, dest);
+    }
+    println!(
+        "Stuck after {} moves with {} pieces left",
+        moves.len(),
+        board.count_peg()
+    );
+    Ok(())
+}
+
+fn svg_solution(board: Board, layout: &str) -> Result<(), String> {
+    if layout != "grid" && layout != "arrows" {
+        return Err(USAGE.to_string());
+    }
+    let moves = find_solution(&board)?;
+    let drawing = if layout == "grid" {
+        svg::solution_grid(&board, &moves, 6)
+    } else {
+        svg::annotated_solution(&board, &moves)
+    };
+    let drawing = drawing.map_err(|index| {
+        format!(
+            "Unable to draw the solution: move {} ({}) is not valid",
+            index + 1,
+            notation::format_move(moves[index])
+        )
+    })?;
+    print!("{}", drawing);
+    Ok(())
+}
+
+// Time each position is shown for in the animations
+const ANIMATION_STEP: Duration = Duration::from_millis(800);
+
+fn read_record(path: &str) -> Result<Record, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Unable to read \"{}\": {}", path, e))?;
+    let invalid = |error: &dyn std::fmt::Display| {
+        format!("\"{}\" is not a valid game record: {}", path, error)
+    };
+    if path.ends_with(".bin") {
+        return serial::from_binary(&bytes).map_err(|e| invalid(&e));
+    }
+    let text = String::from_utf8(bytes).map_err(|e| invalid(&e))?;
+    if path.ends_with(".json") {
+        serial::from_json(&text).map_err(|e| invalid(&e))
+    } else {
+        text.parse().map_err(|e| invalid(&e))
+    }
+}
+
+fn convert(record: Record, output: &str) -> Result<(), String> {
+    let bytes = if output.ends_with(".bin") {
+        serial::to_binary(&record)
+    } else if output.ends_with(".json") {
+        serial::to_json(&record).map(String::into_bytes)
+    } else {
+        Ok(record.to_string().into_bytes())
+    };
+    let bytes = bytes.map_err(|e| format!("Unable to convert the record: {}", e))?;
+    std::fs::write(output, bytes).map_err(|e| format!("Unable to write \"{}\": {}", output, e))
+}
+
+fn animate(record: Record) -> Result<(), String> {
+    let drawing =
+        svg::animation(&record.start, &record.moves, ANIMATION_STEP).map_err(|index| {
+            format!(
+                "Move {} ({}) is not valid",
+                index + 1,
+                notation::format_move(record.moves[index])
+            )
+        })?;
+    print!("{}", drawing);
+    Ok(())
+}
+
+fn animate_solution(board: Board) -> Result<(), String> {
+    let moves = find_solution(&board)?;
+    animate(Record {
+        start: board,
+        moves,
+        elapsed: None,
+    })
+}
+
+fn diff(before: Board, after: Board) -> Result<(), String> {
+    let diff = before
+        .diff(&after)
+        .map_err(|e| format!("Unable to compare the positions: {}", e))?;
+    println!("{:#}", diff);
+    println!("{} cells changed", diff.changes.len());
+    Ok(())
+}
+
+fn list_boards() -> Result<(), String> {
+    for (name, shape) in boards::BOARDS.iter() {
+        let board = Board::new(shape).expect("Unable to make board from the provided string");
+        println!(
+            "{:<10} {}x{}, {} pieces",
+            name,
+            board.width(),
+            board.height(),
+            board.count_peg()
+        );
+    }
+    Ok(())
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(|arg| arg.as_str()).collect();
+    let result = match args.as_slice() {
+        ["play", board] => load_board(board).and_then(play),
+        ["tui", board] => load_board(board).and_then(tui::run),
+        ["solve", board] => load_board(board).and_then(solve),
+        ["check", record] => check(record),
+        ["census", board] => load_board(board).and_then(census),
+        ["fools", board] => load_board(board).and_then(fools),
+        ["list-boards"] => list_boards(),
+        ["diff", before, after] => {
+            load_board(before).and_then(|before| load_board(after).and_then(|a| diff(before, a)))
+        }
+        ["convert", record, output] => read_record(record).and_then(|r| convert(r, output)),
+        ["animate", record] => read_record(record).and_then(animate),
+        ["animate-solution", board] => load_board(board).and_then(animate_solution),
+        ["svg", board] => load_board(board).map(|board| print!("{}", svg::board(&board))),
+        ["svg-solution", board] => load_board(board).and_then(|board| svg_solution(board, "grid")),
+        ["svg-solution", board, layout] => {
+            load_board(board).and_then(|board| svg_solution(board, layout))
+        }
+        _ => Err(USAGE.to_string()),
+    };
+    if let Err(message) = result {
+        eprintln!("{}", message);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,271 @@
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyModifiers},
+    execute, queue,
+    style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor},
+    terminal::{self, ClearType},
+};
+use psolo_core::board::Board;
+use psolo_core::cell::Cell;
+use psolo_core::notation;
+use psolo_core::record::Record;
+use psolo_core::selection::{self, Selection};
+use psolo_core::solver::{Outcome, Solver};
+use std::io::{self, Stdout, Write};
+use std::time::Duration;
+
+const BOARD_LEFT: u16 = 2;
+const BOARD_TOP: u16 = 3;
+const CELL_WIDTH: u16 = 3;
+
+// Jumps tried between two looks at the keyboard while looking for a hint
+const HINT_BUDGET: usize = 100_000;
+
+const KEYS: [&str; 2] = [
+    "[Arrows] move  [Enter] select  [Esc] cancel",
+    "[U] undo  [R] restart  [H] hint  [Q] quit",
+];
+
+struct Tui {
+    record: Record,
+    board: Board,
+    selection: Selection,
+    cursor: (u32, u32),
+    hint: Option<String>,
+    // Search for the hint, done a part at a time so that the keys keep working
+    search: Option<Solver>,
+}
+
+impl Tui {
+    fn new(board: Board) -> Self {
+        let cursor = selection::first_cell(&board).unwrap_or((0, 0));
+        Tui {
+            record: Record::new(board.clone()),
+            board,
+            selection: Selection::default(),
+            cursor,
+            hint: None,
+            search: None,
+        }
+    }
+
+    /// Move the cursor by one cell in the given direction, jumping over the
+    /// unusable cells; the cursor doesn't move if there is no usable cell
+    /// in that direction.
+    fn move_cursor(&mut self, di: i64, dj: i64) {
+        self.cursor = selection::move_cursor(&self.board, self.cursor, (di, dj));
+    }
+
+    fn pick(&mut self) {
+        self.selection.pick(&self.board, Some(self.cursor));
+        if let Some((src, dest)) = self.selection.take_move() {
+            self.board.make_move(src, dest);
+            self.record.moves.push((src, dest));
+            self.hint = None;
+            self.search = None;
+        }
+    }
+
+    fn undo(&mut self) {
+        if let Some((src, dest)) = self.record.moves.pop() {
+            self.board.undo_move(src, dest);
+            self.selection.clear();
+            self.hint = None;
+            self.search = None;
+        }
+    }
+
+    fn restart(&mut self) {
+        self.board = self.record.start.clone();
+        self.record.moves.clear();
+        self.selection.clear();
+        self.hint = None;
+        self.search = None;
+    }
+
+    fn start_hint(&mut self) {
+        match Solver::new(&self.board) {
+            Ok(solver) => {
+                self.search = Some(solver);
+                self.hint = Some("searching... [Esc] stop".to_string());
+            }
+            Err(_) => self.hint = Some("board too large".to_string()),
+        }
+    }
+
+    /// Go on with the search for a hint; whether it is over.
+    fn step_hint(&mut self) -> bool {
+        let outcome = match self.search.as_mut().and_then(|s| s.step(HINT_BUDGET)) {
+            Some(outcome) => outcome,
+            None => return false,
+        };
+        self.search = None;
+        self.hint = Some(match outcome {
+            Outcome::Solved(moves) if !moves.is_empty() => notation::format_move(moves[0]),
+            Outcome::Solved(_) | Outcome::Unsolvable => "no way to win from here".to_string(),
+            Outcome::GaveUp => "no hint found".to_string(),
+        });
+        true
+    }
+
+    fn stop_hint(&mut self) {
+        if self.search.take().is_some() {
+            self.hint = None;
+        }
+    }
+
+    fn draw(&self, stdout: &mut Stdout) -> crossterm::Result<()> {
+        queue!(stdout, terminal::Clear(ClearType::All))?;
+        queue!(
+            stdout,
+            cursor::MoveTo(BOARD_LEFT, 0),
+            SetAttribute(Attribute::Bold),
+            Print("Peg Solitaire"),
+            SetAttribute(Attribute::Reset)
+        )?;
+
+        // Board, with the column letters on top and the row numbers on the left
+        for i in 0..self.board.width() {
+            queue!(
+                stdout,
+                cursor::MoveTo(BOARD_LEFT + 4 + i as u16 * CELL_WIDTH, BOARD_TOP - 1),
+                Print(notation::format_column(i))
+            )?;
+        }
+        for j in 0..self.board.height() {
+            queue!(
+                stdout,
+                cursor::MoveTo(BOARD_LEFT, BOARD_TOP + j as u16),
+                Print(format!("{:>2}", j + 1))
+            )?;
+            for i in 0..self.board.width() {
+                let symbol = match self.board.get_cell(i, j) {
+                    Cell::Peg => "●",
+                    Cell::Hole => "·",
+                    Cell::Unusable => " ",
+                };
+                let color = if self.selection.src == Some((i, j)) {
+                    Color::Red
+                } else if self.board.get_cell(i, j) == Cell::Peg {
+                    Color::Yellow
+                } else {
+                    Color::Blue
+                };
+                queue!(
+                    stdout,
+                    cursor::MoveTo(BOARD_LEFT + 3 + i as u16 * CELL_WIDTH, BOARD_TOP + j as u16)
+                )?;
+                if self.cursor == (i, j) {
+                    queue!(stdout, SetAttribute(Attribute::Reverse))?;
+                }
+                queue!(
+                    stdout,
+                    SetForegroundColor(color),
+                    Print(format!(" {} ", symbol)),
+                    ResetColor,
+                    SetAttribute(Attribute::Reset)
+                )?;
+            }
+        }
+        let below_board = BOARD_TOP + self.board.height() as u16 + 1;
+        for (n, line) in KEYS.iter().enumerate() {
+            queue!(
+                stdout,
+                cursor::MoveTo(BOARD_LEFT, below_board + n as u16),
+                Print(line)
+            )?;
+        }
+
+        // Side panel: pegs left, status, hint and move history
+        let panel_left = BOARD_LEFT + 6 + self.board.width() as u16 * CELL_WIDTH;
+        let n_peg_left = self.board.count_peg();
+        let status = if n_peg_left == 1 {
+            "YOU WIN !!"
+        } else if !self.board.has_valid_move_left() {
+            "No valid move left !"
+        } else {
+            ""
+        };
+        let hint = match &self.hint {
+            Some(hint) => format!("Hint: {}", hint),
+            None => "Hint: press [H]".to_string(),
+        };
+        queue!(
+            stdout,
+            cursor::MoveTo(panel_left, BOARD_TOP),
+            Print(format!("{} pieces left", n_peg_left)),
+            cursor::MoveTo(panel_left, BOARD_TOP + 1),
+            SetForegroundColor(Color::Red),
+            Print(status),
+            ResetColor,
+            cursor::MoveTo(panel_left, BOARD_TOP + 3),
+            Print(hint),
+            cursor::MoveTo(panel_left, BOARD_TOP + 5),
+            Print("Moves:")
+        )?;
+        // Only show the last moves that fit in the terminal
+        let (_, rows) = terminal::size()?;
+        let room = rows.saturating_sub(BOARD_TOP + 7) as usize;
+        let skipped = self.record.moves.len().saturating_sub(room);
+        for (n, &mv) in self.record.moves.iter().enumerate().skip(skipped) {
+            queue!(
+                stdout,
+                cursor::MoveTo(panel_left, BOARD_TOP + 6 + (n - skipped) as u16),
+                Print(format!("{:>3}. {}", n + 1, notation::format_move(mv)))
+            )?;
+        }
+        stdout.flush()?;
+        Ok(())
+    }
+
+    fn main_loop(&mut self, stdout: &mut Stdout) -> crossterm::Result<()> {
+        let mut redraw = true;
+        loop {
+            if redraw {
+                self.draw(stdout)?;
+            }
+            // While looking for a hint, only wait for keys between two
+            // parts of the search
+            if self.search.is_some() && !event::poll(Duration::ZERO)? {
+                redraw = self.step_hint();
+                continue;
+            }
+            redraw = true;
+            if let Event::Key(key) = event::read()? {
+                match key.code {
+                    // Raw mode swallows the interrupt signal
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        return Ok(())
+                    }
+                    KeyCode::Up => self.move_cursor(0, -1),
+                    KeyCode::Down => self.move_cursor(0, 1),
+                    KeyCode::Left => self.move_cursor(-1, 0),
+                    KeyCode::Right => self.move_cursor(1, 0),
+                    KeyCode::Enter | KeyCode::Char(' ') => self.pick(),
+                    KeyCode::Esc => {
+                        self.selection.clear();
+                        self.stop_hint();
+                    }
+                    KeyCode::Char('u') => self.undo(),
+                    KeyCode::Char('r') => self.restart(),
+                    KeyCode::Char('h') => self.start_hint(),
+                    KeyCode::Char('q') => return Ok(()),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// Play on `board` in a full-screen terminal interface.
+pub fn run(board: Board) -> Result<(), String> {
+    let mut stdout = io::stdout();
+    let mut tui = Tui::new(board);
+    let result = terminal::enable_raw_mode()
+        .and_then(|_| execute!(stdout, terminal::EnterAlternateScreen, cursor::Hide))
+        .and_then(|_| tui.main_loop(&mut stdout));
+    // Give the terminal back in its original state, even after an error
+    let _ = execute!(stdout, cursor::Show, terminal::LeaveAlternateScreen);
+    let _ = terminal::disable_raw_mode();
+    result.map_err(|e| e.to_string())
+}
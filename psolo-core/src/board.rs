@@ -0,0 +1,501 @@
+use crate::cell::Cell;
+use crate::notation;
+use core::fmt;
+use core::str::FromStr;
+
+/// A jump, as the coordinates of the moved peg and of its destination.
+pub type Move = ((u32, u32), (u32, u32));
+
+/// A board and the pegs on it.
+///
+/// It is written as its shape, which `parse` (or `Board::new`) reads back,
+/// or with the `#` flag with the column letters and row numbers of the
+/// notation around it:
+///
+/// ```
+/// use psolo_core::board::Board;
+///
+/// let board: Board = " XX\nXOX".parse().unwrap();
+/// assert_eq!(board.to_string(), " XX\nXOX");
+/// assert_eq!(board.to_string().parse(), Ok(board.clone()));
+/// assert_eq!(format!("{:#}", board), "    a b c\n  1   X X\n  2 X O X");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Board {
+    width: u32,
+    height: u32,
+    cells: Vec<Cell>,
+}
+
+impl Board {
+    /// Make a board from a shape, one line per row, with `X` for a peg,
+    /// `O` for a hole and a space for an unusable cell.
+    ///
+    /// Fails if the shape contains another character, if it has an empty
+    /// line or if its lines are not all of the same length.
+    pub fn new(shape: &str) -> Result<Self, ParseError> {
+        let mut cells = Vec::new();
+        let mut width = 0;
+        let mut height = 0;
+        for part in shape.split("\n") {
+            height += 1;
+            let error = |kind| ParseError {
+                line: height as usize,
+                kind,
+            };
+            for char in part.chars() {
+                let cell = Cell::from_str(&char.to_string())
+                    .map_err(|_| error(ParseErrorKind::InvalidCell(char)))?;
+                cells.push(cell);
+            }
+            let length = part.chars().count() as u32;
+            if length == 0 {
+                return Err(error(ParseErrorKind::EmptyLine));
+            } else if width == 0 {
+                width = length;
+            } else if width != length {
+                return Err(error(ParseErrorKind::UnevenLine {
+                    expected: width as usize,
+                    found: length as usize,
+                }));
+            }
+        }
+        Ok(Board {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Number of rows.
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Number of columns.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The first usable cell met when going from `from` by steps of `di`
+    /// columns and `dj` rows, if any before leaving the board.
+    pub(crate) fn next_usable_cell(
+        &self,
+        from: (u32, u32),
+        (di, dj): (i64, i64),
+    ) -> Option<(u32, u32)> {
+        let (mut i, mut j) = (from.0 as i64, from.1 as i64);
+        if di == 0 && dj == 0 {
+            return None;
+        }
+        loop {
+            i += di;
+            j += dj;
+            if i < 0 || j < 0 || i >= self.width as i64 || j >= self.height as i64 {
+                return None;
+            }
+            if self.get_cell(i as u32, j as u32) != Cell::Unusable {
+                return Some((i as u32, j as u32));
+            }
+        }
+    }
+
+    /// Index of the cell `(i, j)` when the cells are stored row by row.
+    pub(crate) fn get_index(&self, i: u32, j: u32) -> usize {
+        (i + j * self.width) as usize
+    }
+
+    /// Content of the cell `(i, j)`.
+    pub fn get_cell(&self, i: u32, j: u32) -> Cell {
+        self.cells[self.get_index(i, j)]
+    }
+
+    /// Number of pegs left on the board.
+    pub fn count_peg(&self) -> usize {
+        self.cells.iter().filter(|&n| *n == Cell::Peg).count()
+    }
+
+    /// Whether the peg on `src` can jump over a neighbouring peg to land on
+    /// the hole `dest`, two cells away on the same row or column.
+    pub fn is_valid_move(&self, src: (u32, u32), dest: (u32, u32)) -> bool {
+        let (i_dest, j_dest) = (i64::from(dest.0), i64::from(dest.1));
+        let (i_src, j_src) = (i64::from(src.0), i64::from(src.1));
+        let (width, height) = (i64::from(self.width), i64::from(self.height));
+        let (i_middle, j_middle) = ((i_src + i_dest) / 2, (j_src + j_dest) / 2);
+        let index = |i: i64, j: i64| (i + j * width) as usize;
+
+        i_src < width
+            && j_src < height
+            && i_dest < width
+            && j_dest < height
+            && (i_src == i_dest && (j_src == j_dest - 2 || j_src == j_dest + 2)
+                || j_src == j_dest && (i_src == i_dest - 2 || i_src == i_dest + 2))
+            && self.cells[index(i_src, j_src)] == Cell::Peg
+            && self.cells[index(i_middle, j_middle)] == Cell::Peg
+            && self.cells[index(i_dest, j_dest)] == Cell::Hole
+    }
+
+    /// Move the peg on `src` to `dest` and remove the peg it jumps over.
+    /// The move must be valid (see `is_valid_move`).
+    pub fn make_move(&mut self, src: (u32, u32), dest: (u32, u32)) {
+        let (i_dest, j_dest) = dest;
+        let (i_src, j_src) = src;
+        let (i_middle, j_middle) = if i_dest == i_src {
+            if j_dest > j_src {
+                (i_dest, j_dest - 1)
+            } else {
+                (i_dest, j_dest + 1)
+            }
+        } else {
+            if i_dest > i_src {
+                (i_dest - 1, j_dest)
+            } else {
+                (i_dest + 1, j_dest)
+            }
+        };
+
+        self.cells[(i_dest + j_dest * self.width) as usize] = Cell::Peg;
+        self.cells[(i_src + j_src * self.width) as usize] = Cell::Hole;
+        self.cells[(i_middle + j_middle * self.width) as usize] = Cell::Hole;
+    }
+
+    /// Take back a move previously done with `make_move`.
+    pub fn undo_move(&mut self, src: (u32, u32), dest: (u32, u32)) {
+        let (i_middle, j_middle) = ((src.0 + dest.0) / 2, (src.1 + dest.1) / 2);
+        self.cells[(dest.0 + dest.1 * self.width) as usize] = Cell::Hole;
+        self.cells[(src.0 + src.1 * self.width) as usize] = Cell::Peg;
+        self.cells[(i_middle + j_middle * self.width) as usize] = Cell::Peg;
+    }
+
+    /// Every hole the peg on `src` can jump to.
+    pub fn destinations_from(&self, (i, j): (u32, u32)) -> Vec<(u32, u32)> {
+        let destinations = [
+            (i.checked_sub(2), Some(j)),
+            (i.checked_add(2), Some(j)),
+            (Some(i), j.checked_sub(2)),
+            (Some(i), j.checked_add(2)),
+        ];
+        destinations
+            .iter()
+            .filter_map(|dest| match *dest {
+                (Some(i_dest), Some(j_dest))
+                    if i_dest < self.width
+                        && j_dest < self.height
+                        && self.is_valid_move((i, j), (i_dest, j_dest)) =>
+                {
+                    Some((i_dest, j_dest))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Whether the peg on `src` has at least one valid move.
+    pub fn can_move(&self, src: (u32, u32)) -> bool {
+        !self.destinations_from(src).is_empty()
+    }
+
+    /// Every valid move on the board.
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let mut moves = Vec::new();
+        for i in 0..self.width {
+            for j in 0..self.height {
+                if self.get_cell(i, j) == Cell::Peg {
+                    for dest in self.destinations_from((i, j)) {
+                        moves.push(((i, j), dest));
+                    }
+                }
+            }
+        }
+        moves
+    }
+
+    /// Whether at least one valid move is left.
+    pub fn has_valid_move_left(&self) -> bool {
+        !self.legal_moves().is_empty()
+    }
+
+    /// The cells whose content differs on `after`, a position of a board of
+    /// the same size.
+    ///
+    /// Fails if the boards are not of the same size.
+    ///
+    /// ```
+    /// use psolo_core::board::Board;
+    ///
+    /// let before = Board::new("XXO").unwrap();
+    /// let mut after = before.clone();
+    /// after.make_move((0, 0), (2, 0));
+    /// let diff = before.diff(&after).unwrap();
+    /// assert_eq!(diff.changes.len(), 3);
+    /// assert_eq!(diff.to_string(), "--+");
+    /// ```
+    pub fn diff(&self, after: &Board) -> Result<Diff, SizeMismatch> {
+        if self.width != after.width || self.height != after.height {
+            return Err(SizeMismatch {
+                before: (self.width, self.height),
+                after: (after.width, after.height),
+            });
+        }
+        let mut changes = Vec::new();
+        for j in 0..self.height {
+            for i in 0..self.width {
+                let (before_cell, after_cell) = (self.get_cell(i, j), after.get_cell(i, j));
+                if before_cell != after_cell {
+                    changes.push(Change {
+                        cell: (i, j),
+                        before: before_cell,
+                        after: after_cell,
+                    });
+                }
+            }
+        }
+        Ok(Diff {
+            after: after.clone(),
+            changes,
+        })
+    }
+}
+
+impl FromStr for Board {
+    type Err = ParseError;
+    fn from_str(input: &str) -> Result<Board, Self::Err> {
+        Board::new(input)
+    }
+}
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_grid(f, self, |i, j| shape_char(self.get_cell(i, j)))
+    }
+}
+
+/// Why a shape can't be read as a board.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Line of the shape where reading stopped, counting from 1.
+    pub line: usize,
+    /// What is wrong with this line.
+    pub kind: ParseErrorKind,
+}
+
+/// What is wrong with a line of a shape.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// A character other than `X`, `O` and a space.
+    InvalidCell(char),
+    /// A line without any cell.
+    EmptyLine,
+    /// A line whose length differs from the first one.
+    UnevenLine {
+        /// Length of the first line.
+        expected: usize,
+        /// Length of this line.
+        found: usize,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match self.kind {
+            ParseErrorKind::InvalidCell(c) => write!(f, "{:?} is not a cell (X, O or a space)", c),
+            ParseErrorKind::EmptyLine => write!(f, "empty line"),
+            ParseErrorKind::UnevenLine { expected, found } => {
+                write!(f, "{} cells instead of {}", found, expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Sizes of two boards that can't be compared, as given by `Board::diff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeMismatch {
+    /// Width and height of the first board.
+    pub before: (u32, u32),
+    /// Width and height of the second board.
+    pub after: (u32, u32),
+}
+
+impl fmt::Display for SizeMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "boards of different sizes ({}x{} and {}x{})",
+            self.before.0, self.before.1, self.after.0, self.after.1
+        )
+    }
+}
+
+impl std::error::Error for SizeMismatch {}
+
+/// A cell whose content differs between two positions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Change {
+    /// Coordinates of the cell.
+    pub cell: (u32, u32),
+    /// Content of the cell on the first position.
+    pub before: Cell,
+    /// Content of the cell on the second position.
+    pub after: Cell,
+}
+
+/// The cells that differ between two positions, as given by `Board::diff`.
+///
+/// It is written as the second position where a peg that appeared is a
+/// `+`, a peg that went is a `-` and any other change a `~`, and with the
+/// `#` flag with the labels of the notation around it (as a `Board`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diff {
+    after: Board,
+    /// Cells that differ, row by row.
+    pub changes: Vec<Change>,
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write_grid(f, &self.after, |i, j| {
+            match self.changes.iter().find(|change| change.cell == (i, j)) {
+                Some(Change {
+                    after: Cell::Peg, ..
+                }) => '+',
+                Some(Change {
+                    before: Cell::Peg, ..
+                }) => '-',
+                Some(_) => '~',
+                None => shape_char(self.after.get_cell(i, j)),
+            }
+        })
+    }
+}
+
+fn shape_char(cell: Cell) -> char {
+    match cell {
+        Cell::Peg => 'X',
+        Cell::Hole => 'O',
+        Cell::Unusable => ' ',
+    }
+}
+
+/// Write a character for each cell of `board`, row by row, labelled as in
+/// the notation when the `#` flag is given.
+fn write_grid(
+    f: &mut fmt::Formatter,
+    board: &Board,
+    char_at: impl Fn(u32, u32) -> char,
+) -> fmt::Result {
+    let labelled = f.alternate();
+    // Every column is as wide as the longest label, past `z`
+    let column = notation::format_column(board.width.saturating_sub(1)).len();
+    if labelled {
+        write!(f, "   ")?;
+        for i in 0..board.width {
+            write!(f, " {:>w$}", notation::format_column(i), w = column)?;
+        }
+    }
+    for j in 0..board.height {
+        if labelled {
+            write!(f, "\n{:>3}", j + 1)?;
+        } else if j > 0 {
+            writeln!(f)?;
+        }
+        for i in 0..board.width {
+            if labelled {
+                write!(f, " {:>w$}", char_at(i, j), w = column)?;
+            } else {
+                write!(f, "{}", char_at(i, j))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Cells used to be indexed with the height instead of the width, which
+    // only went unnoticed on square boards
+    #[test]
+    fn non_square_board() {
+        let mut board = Board::new("XXO\nXOO").unwrap();
+        assert_eq!(board.get_cell(2, 0), Cell::Hole);
+        assert_eq!(board.get_cell(0, 1), Cell::Peg);
+        assert_eq!(board.get_cell(1, 1), Cell::Hole);
+        assert!(board.is_valid_move((0, 0), (2, 0)));
+        board.make_move((0, 0), (2, 0));
+        assert_eq!(board.get_cell(0, 0), Cell::Hole);
+        assert_eq!(board.get_cell(1, 0), Cell::Hole);
+        assert_eq!(board.get_cell(2, 0), Cell::Peg);
+        assert_eq!(board.get_cell(0, 1), Cell::Peg);
+        board.undo_move((0, 0), (2, 0));
+        assert_eq!(board.get_cell(0, 0), Cell::Peg);
+        assert_eq!(board.legal_moves(), vec![((0, 0), (2, 0))]);
+
+        // A column, where a row index based on the height runs past the cells
+        let mut board = Board::new("X\nX\nO").unwrap();
+        assert!(board.is_valid_move((0, 0), (0, 2)));
+        board.make_move((0, 0), (0, 2));
+        assert_eq!(board.get_cell(0, 2), Cell::Peg);
+        assert_eq!(board.count_peg(), 1);
+    }
+
+    // Coordinates used to be compared as i32, so those from 2^31 wrapped
+    // to negative numbers and passed the bounds checks
+    #[test]
+    fn out_of_range_moves() {
+        let board = Board::new("XXO\nXOO").unwrap();
+        assert!(!board.is_valid_move((3, 0), (1, 0)));
+        assert!(!board.is_valid_move((0, 0), (0, 2)));
+        assert!(!board.is_valid_move((u32::MAX, 0), (1, 0)));
+        assert!(!board.is_valid_move((0, u32::MAX), (0, 1)));
+        assert!(!board.is_valid_move((1 << 31, 0), (0, 0)));
+        assert!(!board.is_valid_move((0, 0), (u32::MAX - 1, 0)));
+        assert!(board.destinations_from((u32::MAX, u32::MAX)).is_empty());
+    }
+
+    #[test]
+    fn empty_lines() {
+        assert!(Board::new("").is_err());
+        assert!(Board::new("\nXXO").is_err());
+        assert!(Board::new("XXO\n").is_err());
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = |line, kind| Err(ParseError { line, kind });
+        assert_eq!(
+            Board::new("XXO\nX-O"),
+            error(2, ParseErrorKind::InvalidCell('-'))
+        );
+        assert_eq!(Board::new("XXO\n"), error(2, ParseErrorKind::EmptyLine));
+        assert_eq!(
+            Board::new("XXO\nXO"),
+            error(
+                2,
+                ParseErrorKind::UnevenLine {
+                    expected: 3,
+                    found: 2
+                }
+            )
+        );
+        assert_eq!(
+            Board::new("XXO").unwrap().diff(&Board::new("XO").unwrap()),
+            Err(SizeMismatch {
+                before: (3, 1),
+                after: (2, 1)
+            })
+        );
+    }
+
+    #[test]
+    fn labels_past_z() {
+        let board = Board::new(&"X".repeat(28)).unwrap();
+        let labelled = format!("{:#}", board);
+        let mut lines = labelled.lines();
+        assert!(lines.next().unwrap().ends_with("  y  z aa ab"));
+        assert!(lines.next().unwrap().ends_with("  X  X  X  X"));
+    }
+}
@@ -0,0 +1,60 @@
+use crate::board::{Board, ParseError};
+
+/// The 33 holes english board, with a central vacancy.
+pub const ENGLISH_BOARD: &str = "  XXX  \n  XXX  \nXXXXXXX\nXXXOXXX\nXXXXXXX\n  XXX  \n  XXX  ";
+/// The 37 holes european (french) board, with a central vacancy.
+pub const EUROPEAN_BOARD: &str = "  XXX  \n XXXXX \nXXXXXXX\nXXXOXXX\nXXXXXXX\n XXXXX \n  XXX  ";
+/// The 45 holes board of J. C. Wiegleb, with a central vacancy.
+pub const WIEGLEB_BOARD: &str =
+    "   XXX   \n   XXX   \n   XXX   \nXXXXXXXXX\nXXXXOXXXX\nXXXXXXXXX\n   XXX   \n   XXX   \n   XXX   ";
+/// The 39 holes asymetric (3-3-2-2) board.
+pub const ASYMETRIC_BOARD: &str =
+    "  XXX   \n  XXX   \n  XXX   \nXXXXXXXX\nXXXOXXXX\nXXXXXXXX\n  XXX   \n  XXX   ";
+
+/// Name and shape of every board shipped with the game.
+pub const BOARDS: [(&str, &str); 4] = [
+    ("english", ENGLISH_BOARD),
+    ("european", EUROPEAN_BOARD),
+    ("asymetric", ASYMETRIC_BOARD),
+    ("wiegleb", WIEGLEB_BOARD),
+];
+
+/// Shape of the shipped board called `name`, as listed in `BOARDS`.
+pub fn shape_by_name(name: &str) -> Option<&'static str> {
+    BOARDS
+        .iter()
+        .find(|(board_name, _)| *board_name == name)
+        .map(|(_, shape)| *shape)
+}
+
+/// Name of the shipped board that `board` is, as listed in `BOARDS`, if it
+/// is one of them in its starting position.
+pub fn name_of(board: &Board) -> Option<&'static str> {
+    let shape = board.to_string();
+    BOARDS
+        .iter()
+        .find(|(_, board_shape)| *board_shape == shape)
+        .map(|(name, _)| *name)
+}
+
+/// Make a board either from the name of one of the shipped boards or from
+/// a shape written with X, O and spaces. Unlike `Board::new`, lines of the
+/// shape may be of different lengths (as text editors tend to strip
+/// trailing spaces): shorter lines are padded with unusable cells.
+///
+/// Fails if the text is neither a known name nor a valid shape.
+pub fn load(name_or_shape: &str) -> Result<Board, ParseError> {
+    if let Some(shape) = shape_by_name(name_or_shape.trim()) {
+        return Board::new(shape);
+    }
+    let lines: Vec<&str> = name_or_shape
+        .trim_end_matches(['\n', '\r'])
+        .lines()
+        .collect();
+    let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+    let shape: Vec<String> = lines
+        .iter()
+        .map(|line| format!("{:width$}", line, width = width))
+        .collect();
+    Board::new(&shape.join("\n"))
+}
@@ -0,0 +1,36 @@
+/// Content of a cell of the board.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub enum Cell {
+    /// A cell holding a peg (`X` in a shape).
+    Peg = 0,
+    /// An empty cell a peg can jump to (`O` in a shape).
+    Hole = 1,
+    /// A cell outside of the playing area (a space in a shape).
+    Unusable = 2,
+}
+
+impl core::str::FromStr for Cell {
+    type Err = InvalidCell;
+    fn from_str(input: &str) -> Result<Cell, Self::Err> {
+        match input {
+            "X" => Ok(Cell::Peg),
+            "O" => Ok(Cell::Hole),
+            " " => Ok(Cell::Unusable),
+            _ => Err(InvalidCell(input.to_string())),
+        }
+    }
+}
+
+/// Text that is none of `X`, `O` and a space, so not a cell of a shape.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidCell(pub String);
+
+impl core::fmt::Display for InvalidCell {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "\"{}\" is not a cell (X, O or a space)", self.0)
+    }
+}
+
+impl std::error::Error for InvalidCell {}
@@ -0,0 +1,196 @@
+use crate::board::Board;
+use crate::boards;
+use crate::cell::Cell;
+use core::fmt;
+
+// A position is written as the id of its layout, a dot, then its pegs as a
+// bit string (one bit per usable cell, row by row, set for a peg) in base32
+// without padding, such as `english.777X774`. Only letters, digits and a
+// dot are used, so that the code can be put in a link as it is.
+
+const ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Id of the layout of `board`, that is of its usable cells whatever the
+/// pegs on them: the name of the shipped board with the same layout, or
+/// else a hash of the layout in hexadecimal.
+pub fn layout_id(board: &Board) -> String {
+    for (name, shape) in boards::BOARDS.iter() {
+        let shipped = Board::new(shape).expect("Unable to make board from the provided string");
+        if same_layout(board, &shipped) {
+            return name.to_string();
+        }
+    }
+    // 32 bits FNV-1a hash of the size and the usable cells
+    let mut hash: u32 = 0x811c_9dc5;
+    let size = [board.width(), board.height()];
+    let cells = (0..board.height())
+        .flat_map(|j| (0..board.width()).map(move |i| (i, j)))
+        .map(|(i, j)| (board.get_cell(i, j) != Cell::Unusable) as u32);
+    for byte in size.iter().copied().chain(cells) {
+        hash ^= byte;
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    format!("{:08x}", hash)
+}
+
+/// Code of the position of `board`, as read by `decode`.
+///
+/// ```
+/// use psolo_core::board::Board;
+/// use psolo_core::{boards, code};
+///
+/// let board = Board::new(boards::ENGLISH_BOARD).unwrap();
+/// assert_eq!(code::encode(&board), "english.777X774");
+/// assert_eq!(code::decode("english.777X774", &[]), Ok(board));
+/// ```
+pub fn encode(board: &Board) -> String {
+    let bits: Vec<bool> = usable_cells(board)
+        .map(|(i, j)| board.get_cell(i, j) == Cell::Peg)
+        .collect();
+    let text: String = bits
+        .chunks(5)
+        .map(|chunk| {
+            let value = (0..5).fold(0, |value, n| {
+                (value << 1) | chunk.get(n).copied().unwrap_or(false) as usize
+            });
+            ALPHABET[value] as char
+        })
+        .collect();
+    format!("{}.{}", layout_id(board), text)
+}
+
+/// Position written in `code`, on one of the shipped boards or on one of
+/// `layouts` (whose pegs don't matter). Letters may be of any case.
+///
+/// Fails if the layout of the code is none of these, or if its pegs can't
+/// be read or don't match the number of usable cells of the layout.
+pub fn decode(code: &str, layouts: &[Board]) -> Result<Board, CodeError> {
+    let (id, text) = code.trim().split_once('.').ok_or(CodeError::MissingDot)?;
+    let id = id.to_lowercase();
+    let layout = match boards::shape_by_name(&id) {
+        Some(shape) => Board::new(shape).expect("Unable to make board from the provided string"),
+        None => layouts
+            .iter()
+            .find(|layout| layout_id(layout) == id)
+            .cloned()
+            .ok_or(CodeError::UnknownLayout(id))?,
+    };
+
+    let mut bits = Vec::new();
+    for c in text.chars() {
+        let value = ALPHABET
+            .iter()
+            .position(|&a| a as char == c.to_ascii_uppercase())
+            .ok_or(CodeError::InvalidCharacter(c))?;
+        bits.extend((0..5).rev().map(|n| value & (1 << n) != 0));
+    }
+    let n_cells = usable_cells(&layout).count();
+    // The last character may only be there for some of its bits, which
+    // have to be unset
+    if bits.len() < n_cells || bits.len() >= n_cells + 5 || bits[n_cells..].contains(&true) {
+        return Err(CodeError::WrongLength);
+    }
+
+    let mut bits = bits.into_iter();
+    let shape: String = layout
+        .to_string()
+        .chars()
+        .map(|c| match c {
+            'X' | 'O' if bits.next() == Some(true) => 'X',
+            'X' | 'O' => 'O',
+            c => c,
+        })
+        .collect();
+    Ok(Board::new(&shape).expect("Unable to make board from the layout"))
+}
+
+/// Why a code can't be read as a position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodeError {
+    /// There is no dot between the layout and the pegs.
+    MissingDot,
+    /// The layout is neither a shipped board nor one of the given ones.
+    UnknownLayout(String),
+    /// A character of the pegs is not one of the code.
+    InvalidCharacter(char),
+    /// The pegs don't match the number of usable cells of the layout.
+    WrongLength,
+}
+
+impl fmt::Display for CodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CodeError::MissingDot => write!(f, "missing dot after the layout"),
+            CodeError::UnknownLayout(id) => write!(f, "unknown layout \"{}\"", id),
+            CodeError::InvalidCharacter(c) => write!(f, "{:?} is not a character of codes", c),
+            CodeError::WrongLength => write!(f, "wrong number of pegs for the layout"),
+        }
+    }
+}
+
+impl std::error::Error for CodeError {}
+
+fn same_layout(a: &Board, b: &Board) -> bool {
+    a.width() == b.width()
+        && a.height() == b.height()
+        && (0..a.height()).all(|j| {
+            (0..a.width()).all(|i| {
+                (a.get_cell(i, j) == Cell::Unusable) == (b.get_cell(i, j) == Cell::Unusable)
+            })
+        })
+}
+
+/// Usable cells of `board`, row by row.
+fn usable_cells(board: &Board) -> impl Iterator<Item = (u32, u32)> + '_ {
+    (0..board.height())
+        .flat_map(move |j| (0..board.width()).map(move |i| (i, j)))
+        .filter(move |&(i, j)| board.get_cell(i, j) != Cell::Unusable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_on_custom_layout() {
+        let board = Board::new(" XO\nXXO").unwrap();
+        let code = encode(&board);
+        assert_eq!(code, format!("{}.W", layout_id(&board)));
+        assert_eq!(
+            decode(&code, &[]),
+            Err(CodeError::UnknownLayout(layout_id(&board)))
+        );
+        // Only the usable cells of the given layouts matter
+        let layout = Board::new(" OO\nOOO").unwrap();
+        assert_eq!(decode(&code, &[layout]), Ok(board));
+        assert_eq!(
+            decode(" English.777x774 ", &[]),
+            decode("english.777X774", &[])
+        );
+    }
+
+    #[test]
+    fn decode_errors() {
+        assert_eq!(decode("english777X774", &[]), Err(CodeError::MissingDot));
+        assert_eq!(
+            decode("square.777X774", &[]),
+            Err(CodeError::UnknownLayout("square".to_string()))
+        );
+        for &c in &['1', '8', '=', '-', 'é'] {
+            assert_eq!(
+                decode(&format!("english.777X77{}", c), &[]),
+                Err(CodeError::InvalidCharacter(c))
+            );
+        }
+        // The english board has 33 usable cells, written with 7 characters
+        // whose last two bits are unset
+        for &pegs in &["", "777X77", "777X774A", "777X775", "777X776"] {
+            assert_eq!(
+                decode(&format!("english.{}", pegs), &[]),
+                Err(CodeError::WrongLength),
+                "{}",
+                pegs
+            );
+        }
+    }
+}
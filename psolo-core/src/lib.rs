@@ -0,0 +1,53 @@
+//! Rules of Peg Solitaire (Solo Noble), independent of any user interface.
+//!
+//! A [`Board`](board::Board) is made from a shape where `X` is a peg, `O`
+//! a hole and a space a cell that can't be used. Cells are addressed by
+//! `(i, j)` coordinates, `i` being the column (from the left) and `j` the
+//! row (from the top).
+//!
+//! ```
+//! use psolo_core::board::Board;
+//! use psolo_core::solver::{self, Outcome};
+//! use psolo_core::{boards, notation};
+//!
+//! let mut board = Board::new(boards::ENGLISH_BOARD).unwrap();
+//! let mv = notation::parse_move("d2-d4").unwrap();
+//! assert!(board.is_valid_move(mv.0, mv.1));
+//! board.make_move(mv.0, mv.1);
+//! assert_eq!(board.count_peg(), 31);
+//!
+//! match solver::solve(&board).unwrap() {
+//!     Outcome::Solved(moves) => assert_eq!(moves.len(), 30),
+//!     _ => unreachable!(),
+//! }
+//! ```
+//!
+//! Functions that can fail tell why with an error type of their module,
+//! such as [`ParseError`](board::ParseError) for a shape that can't be
+//! read; every error type implements `std::error::Error`.
+//!
+//! The `json` feature adds reading and writing boards, cells, moves and
+//! records as JSON, with serde.
+#![warn(missing_docs)]
+
+/// The board and the rules of the game.
+pub mod board;
+/// The boards shipped with the game.
+pub mod boards;
+/// The content of a cell of the board.
+pub mod cell;
+/// Compact text codes of positions, to share them.
+pub mod code;
+/// Text notation of cells and moves.
+pub mod notation;
+/// Record of a game.
+pub mod record;
+/// Selection of a move by the player.
+pub mod selection;
+/// Boards and records in JSON (with the `json` feature) and in a compact
+/// binary format.
+pub mod serial;
+/// Analysis of the positions reachable from a board.
+pub mod solver;
+/// Drawings of boards and solutions as SVG images.
+pub mod svg;
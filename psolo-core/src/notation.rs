@@ -0,0 +1,132 @@
+use crate::board::Move;
+use core::fmt;
+
+// Cells are written with letters for the column, starting at `a` on the
+// left, and a number for the row, starting at `1` on the top, so that the
+// central move on the english board is `d2-d4`. Past `z`, columns go on
+// with two letters as in spreadsheets: `aa`, `ab`... `az`, `ba`...
+
+/// Letters of the column `i`, such as `d` for the fourth column.
+pub fn format_column(i: u32) -> String {
+    let mut letters = Vec::new();
+    let mut n = i as u64 + 1;
+    while n > 0 {
+        n -= 1;
+        letters.push((b'a' + (n % 26) as u8) as char);
+        n /= 26;
+    }
+    letters.iter().rev().collect()
+}
+
+/// Write the cell `(i, j)` in notation, such as `d2`.
+pub fn format_cell((i, j): (u32, u32)) -> String {
+    format!("{}{}", format_column(i), j as u64 + 1)
+}
+
+/// Text that can't be read as a cell or a move in notation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotationError {
+    /// The text.
+    pub input: String,
+}
+
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\"{}\" is not written in notation", self.input)
+    }
+}
+
+impl std::error::Error for NotationError {}
+
+/// Read a cell written in notation.
+///
+/// Fails if the text isn't letters followed by a row number.
+pub fn parse_cell(input: &str) -> Result<(u32, u32), NotationError> {
+    let error = || NotationError {
+        input: input.to_string(),
+    };
+    let digits = input
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .ok_or_else(error)?;
+    let (letters, row) = input.split_at(digits);
+    if letters.is_empty() || !row.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(error());
+    }
+    // Both count from 1 in notation, so they may go one past `u32::MAX`
+    let mut column: u64 = 0;
+    for c in letters.chars() {
+        column = column * 26 + (c.to_ascii_lowercase() as u64 - 'a' as u64 + 1);
+        if column > u32::MAX as u64 + 1 {
+            return Err(error());
+        }
+    }
+    let row: u64 = row.parse().map_err(|_| error())?;
+    if row == 0 || row > u32::MAX as u64 + 1 {
+        return Err(error());
+    }
+    Ok(((column - 1) as u32, (row - 1) as u32))
+}
+
+/// Write a move in notation, such as `d2-d4`.
+pub fn format_move((src, dest): Move) -> String {
+    format!("{}-{}", format_cell(src), format_cell(dest))
+}
+
+/// Read a move written in notation.
+///
+/// Fails if the text isn't two cells separated by a dash.
+pub fn parse_move(input: &str) -> Result<Move, NotationError> {
+    let error = || NotationError {
+        input: input.to_string(),
+    };
+    let (src, dest) = input.trim().split_once('-').ok_or_else(error)?;
+    match (parse_cell(src), parse_cell(dest)) {
+        (Ok(src), Ok(dest)) => Ok((src, dest)),
+        _ => Err(error()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn columns_past_z() {
+        assert_eq!(format_cell((25, 0)), "z1");
+        assert_eq!(format_cell((26, 0)), "aa1");
+        assert_eq!(format_cell((27, 9)), "ab10");
+        assert_eq!(format_cell((701, 0)), "zz1");
+        assert_eq!(format_cell((702, 0)), "aaa1");
+        assert_eq!(parse_cell("AB10"), Ok((27, 9)));
+    }
+
+    #[test]
+    fn round_trip() {
+        for i in (0..2000).chain([u32::MAX - 1, u32::MAX]) {
+            for &j in &[0, 1, 41, u32::MAX] {
+                assert_eq!(parse_cell(&format_cell((i, j))), Ok((i, j)));
+            }
+        }
+        let mv = ((3, 1), (30, 1));
+        assert_eq!(parse_move(&format_move(mv)), Ok(mv));
+    }
+
+    #[test]
+    fn rejects_malformed_cells() {
+        for input in &[
+            "",
+            "a",
+            "3",
+            "a0",
+            "a+3",
+            "a-3",
+            "a 3",
+            "a3b",
+            "é3",
+            "zzzzzzzz1",
+            "a99999999999",
+        ] {
+            assert!(parse_cell(input).is_err(), "{}", input);
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use crate::board::{Board, Move, ParseError};
+use crate::boards;
+use crate::notation::{self, NotationError};
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+
+/// A game, as its starting position and the moves played from it.
+///
+/// In text form, a record is the name of the board (or its shape) followed
+/// by a blank line and the moves in notation, separated by whitespace,
+/// then optionally by another blank line and the time spent, in seconds:
+///
+/// ```text
+/// english
+///
+/// d2-d4 f3-d3 e1-e3 e4-e2
+///
+/// time 25.300
+/// ```
+#[derive(Debug, Clone)]
+pub struct Record {
+    /// Position the game started from.
+    pub start: Board,
+    /// Moves played, in order.
+    pub moves: Vec<Move>,
+    /// Time spent playing, if it was measured.
+    pub elapsed: Option<Duration>,
+}
+
+impl Record {
+    /// A game starting from `start`, where no move has been played yet.
+    pub fn new(start: Board) -> Self {
+        Record {
+            start,
+            moves: Vec::new(),
+            elapsed: None,
+        }
+    }
+
+    /// Play the moves from the starting position and return the resulting
+    /// position, or the index of the first move that isn't legal.
+    pub fn replay(&self) -> Result<Board, usize> {
+        let mut board = self.start.clone();
+        for (index, &(src, dest)) in self.moves.iter().enumerate() {
+            if !board.is_valid_move(src, dest) {
+                return Err(index);
+            }
+            board.make_move(src, dest);
+        }
+        Ok(board)
+    }
+
+    /// Number of moves played, where consecutive jumps made with the same
+    /// peg count as a single move (as usual when looking for the shortest
+    /// solutions).
+    pub fn move_count(&self) -> usize {
+        let mut count = 0;
+        let mut last_dest = None;
+        for &(src, dest) in &self.moves {
+            if last_dest != Some(src) {
+                count += 1;
+            }
+            last_dest = Some(dest);
+        }
+        count
+    }
+}
+
+/// Why a text can't be read as a record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecordError {
+    /// The board is neither a known name nor a valid shape.
+    Board(ParseError),
+    /// A move isn't written in notation.
+    Move(NotationError),
+    /// The time spent isn't `time` followed by a number of seconds, with
+    /// at most three decimals.
+    Time(String),
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordError::Board(error) => write!(f, "invalid board, {}", error),
+            RecordError::Move(error) => write!(f, "invalid move, {}", error),
+            RecordError::Time(time) => write!(f, "invalid time spent \"{}\"", time),
+        }
+    }
+}
+
+impl std::error::Error for RecordError {}
+
+impl FromStr for Record {
+    type Err = RecordError;
+    fn from_str(input: &str) -> Result<Record, Self::Err> {
+        let input = input.replace("\r\n", "\n");
+        let mut parts = input.splitn(3, "\n\n");
+        // `splitn` always gives a first part
+        let start = boards::load(parts.next().unwrap_or("")).map_err(RecordError::Board)?;
+        let moves = parts
+            .next()
+            .unwrap_or("")
+            .split_whitespace()
+            .map(notation::parse_move)
+            .collect::<Result<Vec<Move>, _>>()
+            .map_err(RecordError::Move)?;
+        let elapsed = match parts.next().map(str::trim) {
+            None | Some("") => None,
+            Some(time) => {
+                Some(parse_time(time).ok_or_else(|| RecordError::Time(time.to_string()))?)
+            }
+        };
+        Ok(Record {
+            start,
+            moves,
+            elapsed,
+        })
+    }
+}
+
+// Time spent as written in a record, such as `time 25.300`
+fn parse_time(time: &str) -> Option<Duration> {
+    let time = time.strip_prefix("time")?.trim();
+    let mut parts = time.splitn(2, '.');
+    let seconds: u64 = parts.next()?.parse().ok()?;
+    let millis: u64 = match parts.next() {
+        Some(fraction) if fraction.len() <= 3 => format!("{:0<3}", fraction).parse().ok()?,
+        Some(_) => return None,
+        None => 0,
+    };
+    Some(Duration::from_millis(
+        seconds.checked_mul(1000)?.checked_add(millis)?,
+    ))
+}
+
+impl fmt::Display for Record {
+    /// Write the record in the form read by `FromStr`, naming the board
+    /// when it starts as one of the shipped boards.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match boards::name_of(&self.start) {
+            Some(name) => writeln!(f, "{}", name)?,
+            None => writeln!(f, "{}", self.start)?,
+        }
+        writeln!(f)?;
+        let moves: Vec<String> = self
+            .moves
+            .iter()
+            .map(|&mv| notation::format_move(mv))
+            .collect();
+        writeln!(f, "{}", moves.join(" "))?;
+        if let Some(elapsed) = self.elapsed {
+            writeln!(f)?;
+            writeln!(
+                f,
+                "time {}.{:03}",
+                elapsed.as_secs(),
+                elapsed.subsec_millis()
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+use crate::board::{Board, Move};
+use crate::cell::Cell;
+
+/// The peg picked by the player and the hole it should jump to, shared by
+/// every way of playing (mouse in the game window, cursor in the terminal).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Selection {
+    /// The peg to move.
+    pub src: Option<(u32, u32)>,
+    /// Where to move it.
+    pub dest: Option<(u32, u32)>,
+}
+
+impl Selection {
+    /// Pick the given cell, or `None` when picking outside of the board:
+    /// picking a peg selects it as the source, picking a hole the selected
+    /// peg can jump to selects it as the destination.
+    pub fn pick(&mut self, board: &Board, cell: Option<(u32, u32)>) {
+        match cell {
+            Some(cell) if board.get_cell(cell.0, cell.1) == Cell::Peg => {
+                self.src = Some(cell);
+            }
+            Some(cell) => {
+                if let Some(src) = self.src {
+                    if board.is_valid_move(src, cell) {
+                        self.dest = Some(cell);
+                    }
+                }
+            }
+            None => {
+                self.src = None;
+            }
+        }
+    }
+
+    /// Forget the selected peg and destination.
+    pub fn clear(&mut self) {
+        self.src = None;
+        self.dest = None;
+    }
+
+    /// Return the selected move, if both of its ends are selected, and
+    /// reset the selection.
+    pub fn take_move(&mut self) -> Option<Move> {
+        match (self.src, self.dest) {
+            (Some(src), Some(dest)) => {
+                self.clear();
+                Some((src, dest))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The cell a keyboard cursor starts on: the first usable cell of `board`,
+/// row by row.
+pub fn first_cell(board: &Board) -> Option<(u32, u32)> {
+    (0..board.height())
+        .flat_map(|j| (0..board.width()).map(move |i| (i, j)))
+        .find(|&(i, j)| board.get_cell(i, j) != Cell::Unusable)
+}
+
+/// Where a keyboard cursor on `from` goes when moved by one cell in the
+/// direction `(di, dj)`, jumping over the unusable cells; it stays on
+/// `from` if there is no usable cell in that direction.
+pub fn move_cursor(board: &Board, from: (u32, u32), step: (i64, i64)) -> (u32, u32) {
+    board.next_usable_cell(from, step).unwrap_or(from)
+}
@@ -0,0 +1,523 @@
+use crate::board::{Board, Move};
+use crate::cell::Cell;
+use crate::record::Record;
+use core::convert::{TryFrom, TryInto};
+use core::fmt;
+use core::time::Duration;
+
+// Both formats start with the version of the format they were written
+// with, so that reading an older file can be told apart from reading a
+// broken one. The binary format starts with `MAGIC`, the version and a
+// byte telling what kind of value follows, then:
+//
+// - a cell is a byte (0 for a peg, 1 for a hole, 2 for an unusable cell);
+// - a board is its width and height, then its cells row by row, four to a
+//   byte starting from the low bits;
+// - a move is the column and row of the peg then of its destination;
+// - a record is its starting board, the number of moves and the moves,
+//   then either 0, or 1 followed by the time spent in milliseconds.
+//
+// Numbers are unsigned little-endian integers: coordinates and sizes take
+// two bytes, counts four and times eight. Values that don't fit can't be
+// written.
+
+/// Version of the formats written by this library.
+pub const VERSION: u8 = 1;
+
+/// First bytes of the binary format.
+pub const MAGIC: &[u8; 4] = b"PSOL";
+
+/// Why a value can't be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SerialError {
+    /// The bytes don't start with `MAGIC`.
+    Magic,
+    /// The value was written with a version of the format this library
+    /// doesn't know.
+    Version(u8),
+    /// The value is of another kind than the one asked for.
+    Kind(u8),
+    /// The input ends before the end of the value.
+    Truncated,
+    /// The input goes on after the end of the value.
+    TrailingBytes,
+    /// The value isn't valid, such as a board with an empty row.
+    Invalid,
+    /// A number of the value doesn't fit in the binary format, such as a
+    /// coordinate over 65535.
+    Overflow(u128),
+    /// The text isn't JSON of a value of the type asked for, with the
+    /// message of the JSON reader.
+    Json(String),
+}
+
+impl fmt::Display for SerialError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SerialError::Magic => write!(f, "not in the binary format"),
+            SerialError::Version(version) => write!(f, "unknown version {}", version),
+            SerialError::Kind(kind) => write!(f, "unexpected kind of value {}", kind),
+            SerialError::Truncated => write!(f, "unexpected end of input"),
+            SerialError::TrailingBytes => write!(f, "unexpected bytes after the value"),
+            SerialError::Invalid => write!(f, "invalid value"),
+            SerialError::Overflow(n) => write!(f, "{} is too large for the binary format", n),
+            SerialError::Json(message) => write!(f, "invalid JSON: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for SerialError {}
+
+/// A value that can be written in the binary format.
+pub trait Binary: Sized {
+    /// Byte telling what kind of value is written.
+    const KIND: u8;
+
+    /// Append the value to `out`, without any header.
+    ///
+    /// Fails if a number of the value doesn't fit in the format, `out`
+    /// then holding part of the value.
+    fn write(&self, out: &mut Vec<u8>) -> Result<(), SerialError>;
+
+    /// Read a value from the start of `input`, moving `input` past it.
+    ///
+    /// Fails if `input` doesn't start with a valid value.
+    fn read(input: &mut &[u8]) -> Result<Self, SerialError>;
+}
+
+/// `value` in the binary format, with its header.
+///
+/// ```
+/// use psolo_core::board::Board;
+/// use psolo_core::serial;
+///
+/// let board = Board::new(" XXO \nXOXXO").unwrap();
+/// let bytes = serial::to_binary(&board).unwrap();
+/// assert_eq!(bytes.len(), 4 + 2 + 4 + 3);
+/// assert_eq!(serial::from_binary::<Board>(&bytes), Ok(board));
+/// ```
+///
+/// Fails if a number of the value doesn't fit in the format.
+pub fn to_binary<T: Binary>(value: &T) -> Result<Vec<u8>, SerialError> {
+    let mut out = MAGIC.to_vec();
+    out.push(VERSION);
+    out.push(T::KIND);
+    value.write(&mut out)?;
+    Ok(out)
+}
+
+/// Read a value written by `to_binary`.
+///
+/// Fails if the bytes are not the binary format, were written by a later
+/// version, hold another kind of value or aren't valid.
+pub fn from_binary<T: Binary>(bytes: &[u8]) -> Result<T, SerialError> {
+    let mut input = bytes.strip_prefix(&MAGIC[..]).ok_or(SerialError::Magic)?;
+    let version = read_u8(&mut input)?;
+    if version == 0 || version > VERSION {
+        return Err(SerialError::Version(version));
+    }
+    let kind = read_u8(&mut input)?;
+    if kind != T::KIND {
+        return Err(SerialError::Kind(kind));
+    }
+    let value = T::read(&mut input)?;
+    if !input.is_empty() {
+        return Err(SerialError::TrailingBytes);
+    }
+    Ok(value)
+}
+
+fn take<'a>(input: &mut &'a [u8], n: usize) -> Result<&'a [u8], SerialError> {
+    if input.len() < n {
+        return Err(SerialError::Truncated);
+    }
+    let (head, tail) = input.split_at(n);
+    *input = tail;
+    Ok(head)
+}
+
+fn write_u16(out: &mut Vec<u8>, n: u32) -> Result<(), SerialError> {
+    let n = u16::try_from(n).map_err(|_| SerialError::Overflow(n as u128))?;
+    out.extend_from_slice(&n.to_le_bytes());
+    Ok(())
+}
+
+fn read_u8(input: &mut &[u8]) -> Result<u8, SerialError> {
+    Ok(take(input, 1)?[0])
+}
+
+fn read_u16(input: &mut &[u8]) -> Result<u16, SerialError> {
+    Ok(u16::from_le_bytes(take(input, 2)?.try_into().unwrap()))
+}
+
+fn read_u32(input: &mut &[u8]) -> Result<u32, SerialError> {
+    Ok(u32::from_le_bytes(take(input, 4)?.try_into().unwrap()))
+}
+
+fn read_u64(input: &mut &[u8]) -> Result<u64, SerialError> {
+    Ok(u64::from_le_bytes(take(input, 8)?.try_into().unwrap()))
+}
+
+fn cell_from_u8(value: u8) -> Result<Cell, SerialError> {
+    match value {
+        0 => Ok(Cell::Peg),
+        1 => Ok(Cell::Hole),
+        2 => Ok(Cell::Unusable),
+        _ => Err(SerialError::Invalid),
+    }
+}
+
+impl Binary for Cell {
+    const KIND: u8 = 0;
+
+    fn write(&self, out: &mut Vec<u8>) -> Result<(), SerialError> {
+        out.push(*self as u8);
+        Ok(())
+    }
+
+    fn read(input: &mut &[u8]) -> Result<Self, SerialError> {
+        cell_from_u8(read_u8(input)?)
+    }
+}
+
+impl Binary for Board {
+    const KIND: u8 = 1;
+
+    /// Boards wider or higher than 65535 cells can't be written.
+    fn write(&self, out: &mut Vec<u8>) -> Result<(), SerialError> {
+        write_u16(out, self.width())?;
+        write_u16(out, self.height())?;
+        let cells: Vec<Cell> = (0..self.height())
+            .flat_map(|j| (0..self.width()).map(move |i| (i, j)))
+            .map(|(i, j)| self.get_cell(i, j))
+            .collect();
+        for four in cells.chunks(4) {
+            let byte = four
+                .iter()
+                .enumerate()
+                .fold(0, |byte, (n, &cell)| byte | (cell as u8) << (2 * n));
+            out.push(byte);
+        }
+        Ok(())
+    }
+
+    fn read(input: &mut &[u8]) -> Result<Self, SerialError> {
+        let width = read_u16(input)? as usize;
+        let height = read_u16(input)? as usize;
+        let n_cells = width * height;
+        let bytes = take(input, n_cells.div_ceil(4))?;
+        let mut rows = vec![String::with_capacity(width); height];
+        for n in 0..n_cells {
+            let cell = cell_from_u8(bytes[n / 4] >> (2 * (n % 4)) & 0b11)?;
+            rows[n / width].push(match cell {
+                Cell::Peg => 'X',
+                Cell::Hole => 'O',
+                Cell::Unusable => ' ',
+            });
+        }
+        Board::new(&rows.join("\n")).map_err(|_| SerialError::Invalid)
+    }
+}
+
+impl Binary for Move {
+    const KIND: u8 = 2;
+
+    fn write(&self, out: &mut Vec<u8>) -> Result<(), SerialError> {
+        let ((i_src, j_src), (i_dest, j_dest)) = *self;
+        for &n in &[i_src, j_src, i_dest, j_dest] {
+            write_u16(out, n)?;
+        }
+        Ok(())
+    }
+
+    fn read(input: &mut &[u8]) -> Result<Self, SerialError> {
+        let src = (read_u16(input)? as u32, read_u16(input)? as u32);
+        let dest = (read_u16(input)? as u32, read_u16(input)? as u32);
+        Ok((src, dest))
+    }
+}
+
+impl Binary for Record {
+    const KIND: u8 = 3;
+
+    fn write(&self, out: &mut Vec<u8>) -> Result<(), SerialError> {
+        self.start.write(out)?;
+        let n_moves = self.moves.len();
+        let n_moves = u32::try_from(n_moves).map_err(|_| SerialError::Overflow(n_moves as u128))?;
+        out.extend_from_slice(&n_moves.to_le_bytes());
+        for mv in &self.moves {
+            mv.write(out)?;
+        }
+        match self.elapsed {
+            Some(elapsed) => {
+                let millis = elapsed.as_millis();
+                let millis = u64::try_from(millis).map_err(|_| SerialError::Overflow(millis))?;
+                out.push(1);
+                out.extend_from_slice(&millis.to_le_bytes());
+            }
+            None => out.push(0),
+        }
+        Ok(())
+    }
+
+    fn read(input: &mut &[u8]) -> Result<Self, SerialError> {
+        let start = Board::read(input)?;
+        let n_moves = read_u32(input)? as usize;
+        // Don't trust the count to reserve memory
+        let mut moves = Vec::with_capacity(n_moves.min(input.len() / 8));
+        for _ in 0..n_moves {
+            moves.push(Move::read(input)?);
+        }
+        let elapsed = match read_u8(input)? {
+            0 => None,
+            1 => Some(Duration::from_millis(read_u64(input)?)),
+            _ => return Err(SerialError::Invalid),
+        };
+        Ok(Record {
+            start,
+            moves,
+            elapsed,
+        })
+    }
+}
+
+#[cfg(feature = "json")]
+pub use self::json::{from_json, to_json};
+
+// In JSON, a board is its shape (as read by `Board::new`), a cell its name,
+// a move a pair of `[column, row]` and a record an object whose time spent
+// is in milliseconds. The value is wrapped in an object with the version:
+//
+//     {"version":1,"data":{"start":"XXO","moves":[[[0,0],[2,0]]],"elapsed_ms":null}}
+#[cfg(feature = "json")]
+mod json {
+    use super::{SerialError, VERSION};
+    use crate::board::{Board, Move};
+    use crate::record::Record;
+    use core::convert::TryFrom;
+    use core::time::Duration;
+    use serde::de::{DeserializeOwned, Error};
+    use serde::ser::Error as _;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize)]
+    struct Versioned<'a, T> {
+        version: u8,
+        data: &'a T,
+    }
+
+    // The data is only read once the version is known to be one that can
+    // be read
+    #[derive(Deserialize)]
+    struct Envelope {
+        version: u8,
+        data: serde_json::Value,
+    }
+
+    /// `value` in JSON, with the version of the format.
+    ///
+    /// ```
+    /// use psolo_core::board::Board;
+    /// use psolo_core::record::Record;
+    /// use psolo_core::serial;
+    ///
+    /// let mut record = Record::new(Board::new("XXO").unwrap());
+    /// record.moves.push(((0, 0), (2, 0)));
+    /// let json = serial::to_json(&record).unwrap();
+    /// assert_eq!(
+    ///     json,
+    ///     r#"{"version":1,"data":{"start":"XXO","moves":[[[0,0],[2,0]]],"elapsed_ms":null}}"#
+    /// );
+    /// let read: Record = serial::from_json(&json).unwrap();
+    /// assert_eq!(read.moves, record.moves);
+    /// ```
+    ///
+    /// Fails if the time spent of a record is too long to be written in
+    /// milliseconds.
+    pub fn to_json<T: Serialize>(value: &T) -> Result<String, SerialError> {
+        serde_json::to_string(&Versioned {
+            version: VERSION,
+            data: value,
+        })
+        .map_err(|error| SerialError::Json(error.to_string()))
+    }
+
+    /// Read a value written by `to_json`.
+    ///
+    /// Fails if the text isn't JSON of a value of this type, or was written
+    /// by a later version.
+    pub fn from_json<T: DeserializeOwned>(text: &str) -> Result<T, SerialError> {
+        let json_error = |error: serde_json::Error| SerialError::Json(error.to_string());
+        let envelope: Envelope = serde_json::from_str(text).map_err(json_error)?;
+        if envelope.version == 0 || envelope.version > VERSION {
+            return Err(SerialError::Version(envelope.version));
+        }
+        serde_json::from_value(envelope.data).map_err(json_error)
+    }
+
+    impl Serialize for Board {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(self)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Board {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let shape = String::deserialize(deserializer)?;
+            Board::new(&shape).map_err(D::Error::custom)
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct RecordData {
+        start: Board,
+        moves: Vec<Move>,
+        #[serde(default)]
+        elapsed_ms: Option<u64>,
+    }
+
+    impl Serialize for Record {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let elapsed_ms = match self.elapsed {
+                Some(elapsed) => Some(
+                    u64::try_from(elapsed.as_millis())
+                        .map_err(|_| S::Error::custom("time spent too long"))?,
+                ),
+                None => None,
+            };
+            RecordData {
+                start: self.start.clone(),
+                moves: self.moves.clone(),
+                elapsed_ms,
+            }
+            .serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Record {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let data = RecordData::deserialize(deserializer)?;
+            Ok(Record {
+                start: data.start,
+                moves: data.moves,
+                elapsed: data.elapsed_ms.map(Duration::from_millis),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boards;
+
+    fn record() -> Record {
+        let mut record = Record::new(Board::new(boards::ASYMETRIC_BOARD).unwrap());
+        record.moves = vec![((3, 2), (3, 4)), ((5, 3), (3, 3))];
+        record.elapsed = Some(Duration::from_millis(25_300));
+        record
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let record = record();
+        let read: Record = from_binary(&to_binary(&record).unwrap()).unwrap();
+        assert_eq!(read.start, record.start);
+        assert_eq!(read.moves, record.moves);
+        assert_eq!(read.elapsed, record.elapsed);
+
+        let mv: Move = ((0, 65535), (65535, 2));
+        assert_eq!(from_binary::<Move>(&to_binary(&mv).unwrap()), Ok(mv));
+        for &cell in &[Cell::Peg, Cell::Hole, Cell::Unusable] {
+            assert_eq!(from_binary::<Cell>(&to_binary(&cell).unwrap()), Ok(cell));
+        }
+    }
+
+    #[test]
+    fn bad_headers() {
+        let bytes = to_binary(&record()).unwrap();
+        let mut other = bytes.clone();
+        other[0] = b'X';
+        assert_eq!(
+            from_binary::<Record>(&other).unwrap_err(),
+            SerialError::Magic
+        );
+        for &version in &[0, VERSION + 1] {
+            let mut other = bytes.clone();
+            other[4] = version;
+            assert_eq!(
+                from_binary::<Record>(&other).unwrap_err(),
+                SerialError::Version(version)
+            );
+        }
+        assert_eq!(
+            from_binary::<Board>(&bytes).unwrap_err(),
+            SerialError::Kind(Record::KIND)
+        );
+    }
+
+    #[test]
+    fn truncated_and_trailing_bytes() {
+        let bytes = to_binary(&record()).unwrap();
+        for length in 0..bytes.len() {
+            let error = from_binary::<Record>(&bytes[..length]).unwrap_err();
+            let expected = if length < MAGIC.len() {
+                SerialError::Magic
+            } else {
+                SerialError::Truncated
+            };
+            assert_eq!(error, expected, "{} bytes", length);
+        }
+        let mut longer = bytes;
+        longer.push(0);
+        assert_eq!(
+            from_binary::<Record>(&longer).unwrap_err(),
+            SerialError::TrailingBytes
+        );
+    }
+
+    #[test]
+    fn size_limits() {
+        let wide = Board::new(&"X".repeat(65535)).unwrap();
+        assert_eq!(from_binary::<Board>(&to_binary(&wide).unwrap()), Ok(wide));
+        let too_wide = Board::new(&"X".repeat(65536)).unwrap();
+        assert_eq!(to_binary(&too_wide), Err(SerialError::Overflow(65536)));
+        let mv: Move = ((0, 0), (70_000, 0));
+        assert_eq!(to_binary(&mv), Err(SerialError::Overflow(70_000)));
+
+        // Boards have at least one cell, so a size of 0 is never written
+        let mut empty = MAGIC.to_vec();
+        empty.extend_from_slice(&[VERSION, Board::KIND, 0, 0, 3, 0]);
+        assert_eq!(from_binary::<Board>(&empty), Err(SerialError::Invalid));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_round_trip() {
+        let record = record();
+        let read: Record = from_json(&to_json(&record).unwrap()).unwrap();
+        assert_eq!(read.start, record.start);
+        assert_eq!(read.moves, record.moves);
+        assert_eq!(read.elapsed, record.elapsed);
+
+        let board = Board::new(" XO\nXXO").unwrap();
+        assert_eq!(from_json::<Board>(&to_json(&board).unwrap()), Ok(board));
+        assert_eq!(
+            from_json::<Board>(r#"{"version":2,"data":"XXO"}"#),
+            Err(SerialError::Version(2))
+        );
+        // A later version may write its data in another way
+        assert!(matches!(
+            from_json::<Record>(r#"{"version":2,"data":{"board":[1,2,3]}}"#),
+            Err(SerialError::Version(2))
+        ));
+        match from_json::<Board>(r#"{"version":1,"data":"X\nXO"}"#) {
+            Err(SerialError::Json(message)) => assert!(message.contains("line 2")),
+            result => panic!("{:?}", result),
+        }
+        assert!(matches!(
+            from_json::<Record>(r#"{"version":1,"data":{"start":"XXO""#),
+            Err(SerialError::Json(_))
+        ));
+    }
+}
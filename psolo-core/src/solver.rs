@@ -0,0 +1,577 @@
+use crate::board::{Board, Move};
+use crate::cell::Cell;
+use core::fmt;
+use std::collections::HashSet;
+
+/// A board with more usable cells than the searches can handle, which is
+/// 128.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TooLarge {
+    /// Number of usable cells of the board.
+    pub cells: usize,
+}
+
+impl fmt::Display for TooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "the board has {} usable cells, more than the 128 that can be searched",
+            self.cells
+        )
+    }
+}
+
+impl std::error::Error for TooLarge {}
+
+/// A jump expressed on the bits of a packed position.
+struct Jump {
+    src: u128,
+    over: u128,
+    dest: u128,
+    mv: Move,
+}
+
+impl Jump {
+    fn apply(&self, position: u128) -> u128 {
+        position ^ (self.src | self.over | self.dest)
+    }
+}
+
+/// Positions of a board packed as bitsets (one bit per usable cell),
+/// together with every jump that can happen on that board.
+struct Packed {
+    start: u128,
+    jumps: Vec<Jump>,
+    /// Position class of each bit, see `Packed::class`.
+    classes: Vec<u8>,
+}
+
+impl Packed {
+    fn new(board: &Board) -> Result<Self, TooLarge> {
+        let (width, height) = (board.width() as i64, board.height() as i64);
+        let mut bits = vec![None; (width * height) as usize];
+        let mut n_bits = 0;
+        let mut start = 0;
+        let mut classes = Vec::new();
+        for i in 0..board.width() {
+            for j in 0..board.height() {
+                let cell = board.get_cell(i, j);
+                if cell == Cell::Unusable {
+                    continue;
+                }
+                if n_bits == 128 {
+                    let cells = (0..board.height())
+                        .flat_map(|j| (0..board.width()).map(move |i| (i, j)))
+                        .filter(|&(i, j)| board.get_cell(i, j) != Cell::Unusable)
+                        .count();
+                    return Err(TooLarge { cells });
+                }
+                bits[board.get_index(i, j)] = Some(1u128 << n_bits);
+                if cell == Cell::Peg {
+                    start |= 1u128 << n_bits;
+                }
+                let (i, j) = (i as i64, j as i64);
+                classes.push(((i + j).rem_euclid(3) | (i - j).rem_euclid(3) << 2) as u8);
+                n_bits += 1;
+            }
+        }
+
+        let bit = |i: i64, j: i64| -> Option<u128> {
+            if i < 0 || j < 0 || i >= width || j >= height {
+                None
+            } else {
+                bits[board.get_index(i as u32, j as u32)]
+            }
+        };
+        let mut jumps = Vec::new();
+        for i in 0..width {
+            for j in 0..height {
+                for (di, dj) in &[(1, 0), (-1, 0), (0, 1), (0, -1)] {
+                    if let (Some(src), Some(over), Some(dest)) =
+                        (bit(i, j), bit(i + di, j + dj), bit(i + 2 * di, j + 2 * dj))
+                    {
+                        let mv = (
+                            (i as u32, j as u32),
+                            ((i + 2 * di) as u32, (j + 2 * dj) as u32),
+                        );
+                        jumps.push(Jump {
+                            src,
+                            over,
+                            dest,
+                            mv,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(Packed {
+            start,
+            jumps,
+            classes,
+        })
+    }
+
+    /// Position class of `position`: the parity of the number of pegs on
+    /// each diagonal modulo 3, in both directions. Three cells in a line
+    /// sit on three different diagonals, so a jump flips every parity and
+    /// only positions of the same class can lead to one another.
+    fn class(&self, position: u128) -> u8 {
+        let mut parities = 0u8;
+        for (bit, &class) in self.classes.iter().enumerate() {
+            if position & (1u128 << bit) != 0 {
+                parities ^= 1 << (class & 3) | 8 << (class >> 2);
+            }
+        }
+        // Flipping every parity doesn't change the class
+        if parities & 1 != 0 {
+            parities ^= 0b111;
+        }
+        if parities & 8 != 0 {
+            parities ^= 0b111000;
+        }
+        parities
+    }
+
+    /// Whether a position with a single peg can be reached from `start`,
+    /// as far as position classes tell.
+    fn may_be_solved(&self) -> bool {
+        let class = self.class(self.start);
+        (0..self.classes.len()).any(|bit| self.class(1u128 << bit) == class)
+    }
+
+    fn legal_jumps(&self, position: u128) -> impl Iterator<Item = &Jump> + '_ {
+        self.jumps.iter().filter(move |jump| {
+            position & jump.src != 0 && position & jump.over != 0 && position & jump.dest == 0
+        })
+    }
+
+    fn successors(&self, position: u128) -> impl Iterator<Item = u128> + '_ {
+        self.legal_jumps(position)
+            .map(move |jump| jump.apply(position))
+    }
+}
+
+/// Number of positions reachable with a given number of pegs left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CensusLevel {
+    /// Number of pegs left.
+    pub pegs: u32,
+    /// Number of reachable positions with that many pegs.
+    pub positions: usize,
+    /// How many of these positions can still be reduced to a single peg.
+    pub winnable: usize,
+}
+
+/// Number of positions a census may enumerate before giving up, which keeps
+/// the memory it uses around a hundred megabytes.
+pub const CENSUS_LIMIT: usize = 4_000_000;
+
+/// Why the census of a board couldn't be taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CensusError {
+    /// The board has more usable cells than can be searched.
+    TooLarge(TooLarge),
+    /// More positions are reachable from the board than the census may
+    /// enumerate.
+    TooManyPositions {
+        /// Number of positions the census was allowed to enumerate.
+        limit: usize,
+    },
+}
+
+impl From<TooLarge> for CensusError {
+    fn from(e: TooLarge) -> Self {
+        CensusError::TooLarge(e)
+    }
+}
+
+impl fmt::Display for CensusError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CensusError::TooLarge(e) => e.fmt(f),
+            CensusError::TooManyPositions { limit } => write!(
+                f,
+                "more than {} positions are reachable from the board",
+                limit
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CensusError {}
+
+/// Enumerate every position reachable from `board` and count them by
+/// number of pegs left, along with how many of them can still be reduced
+/// to a single peg. Levels are ordered from the starting position down to
+/// the positions with the fewest pegs.
+///
+/// Fails if the board has more than 128 usable cells, or if more than
+/// `CENSUS_LIMIT` positions are reachable from it.
+pub fn census(board: &Board) -> Result<Vec<CensusLevel>, CensusError> {
+    census_with_limit(board, CENSUS_LIMIT)
+}
+
+/// Same as `census`, giving up when more than `limit` positions are
+/// reachable.
+pub fn census_with_limit(board: &Board, limit: usize) -> Result<Vec<CensusLevel>, CensusError> {
+    let packed = Packed::new(board)?;
+
+    // Every move removes exactly one peg, so positions can be explored
+    // level by level, each level holding the positions of the same peg count.
+    // Levels are kept as sorted vectors, which is much lighter than hash sets
+    // on the larger boards.
+    let mut levels: Vec<Vec<u128>> = vec![vec![packed.start]];
+    let mut total = 1;
+    loop {
+        let mut next = HashSet::new();
+        for &position in levels.last().unwrap() {
+            next.extend(packed.successors(position));
+            if total + next.len() > limit {
+                return Err(CensusError::TooManyPositions { limit });
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        total += next.len();
+        let mut next: Vec<u128> = next.into_iter().collect();
+        next.sort_unstable();
+        levels.push(next);
+    }
+
+    // Walk back up from the last level: a position can still be won if it
+    // has a single peg or if one of its successors can still be won.
+    let mut winnable_below: Vec<u128> = Vec::new();
+    let mut result = Vec::with_capacity(levels.len());
+    while let Some(level) = levels.pop() {
+        let winnable: Vec<u128> = level
+            .iter()
+            .copied()
+            .filter(|&position| {
+                position.count_ones() == 1
+                    || packed
+                        .successors(position)
+                        .any(|next| winnable_below.binary_search(&next).is_ok())
+            })
+            .collect();
+        result.push(CensusLevel {
+            pegs: packed.start.count_ones() - levels.len() as u32,
+            positions: level.len(),
+            winnable: winnable.len(),
+        });
+        winnable_below = winnable;
+    }
+    result.reverse();
+    Ok(result)
+}
+
+/// Search for the shortest game from `board` that ends in a position with no
+/// legal move left ("fool's solitaire"), i.e. where `has_valid_move_left`
+/// would return `false`. As every move removes one peg, the shortest such
+/// game is also the one leaving the most pegs on the board.
+///
+/// Fails if the board has more than 128 usable cells.
+pub fn fools_solitaire(board: &Board) -> Result<Vec<Move>, TooLarge> {
+    let packed = Packed::new(board)?;
+
+    // Breadth-first search, one level per number of moves played, until a
+    // level contains a position where no jump is possible.
+    let mut levels: Vec<Vec<u128>> = vec![vec![packed.start]];
+    let dead_end = loop {
+        let level = levels.last().unwrap();
+        if let Some(&position) = level
+            .iter()
+            .find(|&&position| packed.legal_jumps(position).next().is_none())
+        {
+            break position;
+        }
+        let next: HashSet<u128> = level
+            .iter()
+            .flat_map(|&position| packed.successors(position))
+            .collect();
+        let mut next: Vec<u128> = next.into_iter().collect();
+        next.sort_unstable();
+        levels.push(next);
+    };
+
+    // Rebuild the game backward: from the dead end, look for a jump leading
+    // to it from a position of the previous level.
+    let mut moves = Vec::new();
+    let mut position = dead_end;
+    levels.pop();
+    while let Some(level) = levels.pop() {
+        let (parent, jump) = packed
+            .jumps
+            .iter()
+            .filter(|jump| position & jump.dest != 0 && position & (jump.src | jump.over) == 0)
+            .map(|jump| (jump.apply(position), jump))
+            .find(|(parent, _)| level.binary_search(parent).is_ok())
+            .unwrap();
+        moves.push(jump.mv);
+        position = parent;
+    }
+    moves.reverse();
+    Ok(moves)
+}
+
+/// Number of positions a search may rule out before giving up, which keeps
+/// the memory used by the search below a hundred megabytes.
+pub const SEARCH_LIMIT: usize = 2_000_000;
+
+/// How a search for a solution ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// The moves reducing the board to a single peg.
+    Solved(Vec<Move>),
+    /// There is no way to reduce the board to a single peg.
+    Unsolvable,
+    /// The search was stopped before finding an answer, after ruling out
+    /// `SEARCH_LIMIT` positions.
+    GaveUp,
+}
+
+/// A search for a sequence of moves reducing a board to a single peg,
+/// done a few positions at a time so that it can be spread over several
+/// frames of a game.
+///
+/// ```
+/// use psolo_core::board::Board;
+/// use psolo_core::solver::{Outcome, Solver};
+///
+/// let board = Board::new("XXO").unwrap();
+/// let mut solver = Solver::new(&board).unwrap();
+/// let outcome = loop {
+///     if let Some(outcome) = solver.step(1000) {
+///         break outcome;
+///     }
+/// };
+/// assert_eq!(outcome, Outcome::Solved(vec![((0, 0), (2, 0))]));
+/// ```
+pub struct Solver {
+    packed: Packed,
+    /// Positions known not to lead to a single peg.
+    dead_ends: HashSet<u128>,
+    /// Positions of the current line of play, with the index of the next
+    /// jump to try from each of them.
+    stack: Vec<(u128, usize)>,
+    moves: Vec<Move>,
+    limit: usize,
+    result: Option<Outcome>,
+}
+
+impl Solver {
+    /// Start a search from `board`, that gives up after ruling out
+    /// `SEARCH_LIMIT` positions.
+    ///
+    /// Fails if the board has more than 128 usable cells.
+    pub fn new(board: &Board) -> Result<Self, TooLarge> {
+        Solver::with_limit(board, SEARCH_LIMIT)
+    }
+
+    /// Start a search from `board`, that gives up after ruling out `limit`
+    /// positions.
+    ///
+    /// Fails if the board has more than 128 usable cells.
+    pub fn with_limit(board: &Board, limit: usize) -> Result<Self, TooLarge> {
+        let packed = Packed::new(board)?;
+        let start = packed.start;
+        let result = if packed.may_be_solved() {
+            None
+        } else {
+            Some(Outcome::Unsolvable)
+        };
+        Ok(Solver {
+            packed,
+            dead_ends: HashSet::new(),
+            stack: vec![(start, 0)],
+            moves: Vec::new(),
+            limit,
+            result,
+        })
+    }
+
+    /// Go on with the search for at most `budget` jumps, depth first.
+    /// Returns `None` while the search is not over, then how it ended.
+    pub fn step(&mut self, budget: usize) -> Option<Outcome> {
+        for _ in 0..budget {
+            if self.result.is_some() {
+                break;
+            }
+            let (position, next) = match self.stack.last_mut() {
+                Some(frame) => (frame.0, &mut frame.1),
+                None => {
+                    self.result = Some(Outcome::Unsolvable);
+                    break;
+                }
+            };
+            if position.count_ones() == 1 {
+                self.result = Some(Outcome::Solved(self.moves.clone()));
+                break;
+            }
+            let jumps = &self.packed.jumps;
+            let legal = jumps[*next..].iter().position(|jump| {
+                position & jump.src != 0 && position & jump.over != 0 && position & jump.dest == 0
+            });
+            match legal {
+                Some(k) => {
+                    let jump = &jumps[*next + k];
+                    *next += k + 1;
+                    let child = jump.apply(position);
+                    if !self.dead_ends.contains(&child) {
+                        self.moves.push(jump.mv);
+                        self.stack.push((child, 0));
+                    }
+                }
+                // Every jump from there has been tried
+                None => {
+                    if self.dead_ends.len() == self.limit {
+                        self.result = Some(Outcome::GaveUp);
+                        break;
+                    }
+                    self.dead_ends.insert(position);
+                    self.stack.pop();
+                    self.moves.pop();
+                }
+            }
+        }
+        self.result.clone()
+    }
+}
+
+/// Look for a sequence of moves reducing `board` to a single peg, giving
+/// up after ruling out `SEARCH_LIMIT` positions.
+///
+/// Fails if the board has more than 128 usable cells.
+pub fn solve(board: &Board) -> Result<Outcome, TooLarge> {
+    let mut solver = Solver::new(board)?;
+    loop {
+        if let Some(outcome) = solver.step(usize::MAX) {
+            return Ok(outcome);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boards;
+
+    // Worked out by hand: from OXXOX, a1 can only get stuck on XOOOX or
+    // go on with OOOXX then OOXOO
+    #[test]
+    fn census_of_small_board() {
+        let board = Board::new("OXXOX").unwrap();
+        let level = |pegs, positions, winnable| CensusLevel {
+            pegs,
+            positions,
+            winnable,
+        };
+        assert_eq!(
+            census(&board).unwrap(),
+            vec![level(3, 1, 1), level(2, 2, 1), level(1, 1, 1)]
+        );
+    }
+
+    // Same census, by playing every game on `Board` itself
+    #[test]
+    fn census_matches_playing_every_game() {
+        let start = Board::new("XXXX\nXXOX\nXXXX").unwrap();
+        let mut level = vec![start.clone()];
+        let mut positions = Vec::new();
+        while !level.is_empty() {
+            positions.push(level.len());
+            let mut next: Vec<Board> = Vec::new();
+            for board in &level {
+                for (src, dest) in board.legal_moves() {
+                    let mut child = board.clone();
+                    child.make_move(src, dest);
+                    if !next.contains(&child) {
+                        next.push(child);
+                    }
+                }
+            }
+            level = next;
+        }
+        let levels = census(&start).unwrap();
+        let counted: Vec<usize> = levels.iter().map(|level| level.positions).collect();
+        assert_eq!(counted, positions);
+        assert_eq!(levels[0].pegs, 11);
+    }
+
+    // The english board has millions of reachable positions
+    #[test]
+    fn census_gives_up_at_limit() {
+        let board = Board::new(boards::ENGLISH_BOARD).unwrap();
+        assert_eq!(
+            census_with_limit(&board, 10_000),
+            Err(CensusError::TooManyPositions { limit: 10_000 })
+        );
+        let board = Board::new("OXXOX").unwrap();
+        assert_eq!(
+            census_with_limit(&board, 3),
+            Err(CensusError::TooManyPositions { limit: 3 })
+        );
+        assert!(census_with_limit(&board, 4).is_ok());
+    }
+
+    // The shortest fool's solitaire on the english board is known to take
+    // six jumps
+    #[test]
+    fn fools_solitaire_on_english() {
+        let mut board = Board::new(boards::ENGLISH_BOARD).unwrap();
+        let moves = fools_solitaire(&board).unwrap();
+        assert_eq!(moves.len(), 6);
+        for &(src, dest) in &moves {
+            assert!(board.is_valid_move(src, dest));
+            board.make_move(src, dest);
+        }
+        assert!(!board.has_valid_move_left());
+        assert_eq!(board.count_peg(), 26);
+    }
+
+    #[test]
+    fn fools_solitaire_on_small_boards() {
+        // Stuck at once on XOOOX, rather than going on to OOXOO
+        let board = Board::new("OXXOX").unwrap();
+        assert_eq!(fools_solitaire(&board).unwrap(), vec![((2, 0), (0, 0))]);
+        // Already stuck
+        let board = Board::new("XOX").unwrap();
+        assert_eq!(fools_solitaire(&board).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn solves_english() {
+        let board = Board::new(boards::ENGLISH_BOARD).unwrap();
+        match solve(&board).unwrap() {
+            Outcome::Solved(moves) => assert_eq!(moves.len(), 31),
+            outcome => panic!("{:?}", outcome),
+        }
+    }
+
+    // The european board with a central vacancy is the classic example of
+    // a position class that can't lead to a single peg
+    #[test]
+    fn rejects_european_by_class() {
+        let board = Board::new(boards::EUROPEAN_BOARD).unwrap();
+        let mut solver = Solver::new(&board).unwrap();
+        assert_eq!(solver.step(0), Some(Outcome::Unsolvable));
+    }
+
+    #[test]
+    fn gives_up_at_limit() {
+        let board = Board::new(boards::WIEGLEB_BOARD).unwrap();
+        let mut solver = Solver::with_limit(&board, 1000).unwrap();
+        let outcome = loop {
+            if let Some(outcome) = solver.step(usize::MAX) {
+                break outcome;
+            }
+        };
+        assert_eq!(outcome, Outcome::GaveUp);
+    }
+
+    #[test]
+    fn proves_small_board_unsolvable() {
+        let board = Board::new("XOX").unwrap();
+        assert_eq!(solve(&board).unwrap(), Outcome::Unsolvable);
+    }
+}
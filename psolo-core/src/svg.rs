@@ -0,0 +1,275 @@
+use crate::board::{Board, Move};
+use crate::cell::Cell;
+use crate::notation;
+use core::fmt::Write;
+use core::time::Duration;
+
+// Drawings are made of plain SVG elements, with sizes in user units, so
+// that they can be written without any window or graphics library and
+// scaled freely by whatever shows them.
+
+/// Side of a cell.
+const CELL: u32 = 40;
+/// Room left around a diagram, and between the diagrams of a grid.
+const MARGIN: u32 = 20;
+/// Height of the caption written under each diagram of a grid.
+const CAPTION: u32 = 24;
+
+const BOARD_COLOR: &str = "#E8D9B5";
+const HOLE_COLOR: &str = "#8C7B5A";
+const PEG_COLOR: &str = "#202020";
+const JUMPED_COLOR: &str = "#B0B0B0";
+const ARROW_COLOR: &str = "#D02020";
+
+/// A drawing of `board` with its pegs and holes, as an SVG document.
+///
+/// ```
+/// use psolo_core::board::Board;
+/// use psolo_core::svg;
+///
+/// let drawing = svg::board(&Board::new("XXO").unwrap());
+/// assert!(drawing.starts_with("<svg"));
+/// assert_eq!(drawing.matches("<circle").count(), 3);
+/// ```
+pub fn board(board: &Board) -> String {
+    let (width, height) = diagram_size(board);
+    let mut out = header(width + 2 * MARGIN, height + 2 * MARGIN);
+    diagram(&mut out, board, MARGIN, MARGIN);
+    out.push_str("</svg>\n");
+    out
+}
+
+/// Diagrams of the positions met when playing `moves` from `start`, laid
+/// out on a grid of `columns` columns. The first diagram is the starting
+/// position; each of the others is numbered, shows the jump leading to it
+/// and the peg it removed.
+///
+/// Fails with the index of the first move that isn't legal.
+pub fn solution_grid(start: &Board, moves: &[Move], columns: usize) -> Result<String, usize> {
+    let columns = columns.max(1).min(moves.len() + 1) as u32;
+    let rows = (moves.len() as u32 + columns) / columns;
+    let (width, height) = diagram_size(start);
+    let (step_x, step_y) = (width + MARGIN, height + CAPTION + MARGIN);
+    let mut out = header(columns * step_x + MARGIN, rows * step_y + MARGIN);
+    out.push_str(&arrow_marker());
+
+    let mut board = start.clone();
+    for n in 0..=moves.len() {
+        let x = MARGIN + (n as u32 % columns) * step_x;
+        let y = MARGIN + (n as u32 / columns) * step_y;
+        let last = n.checked_sub(1).map(|index| (index, moves[index]));
+        if let Some((index, (src, dest))) = last {
+            if !board.is_valid_move(src, dest) {
+                return Err(index);
+            }
+            board.make_move(src, dest);
+        }
+        frame(&mut out, &board, last, x, y);
+    }
+    out.push_str("</svg>\n");
+    Ok(out)
+}
+
+/// An animation of the positions met when playing `moves` from `start`,
+/// showing each of them for `step`, with the same diagrams as
+/// `solution_grid`. The final position is held a little longer, then the
+/// animation starts over.
+///
+/// Fails with the index of the first move that isn't legal.
+///
+/// ```
+/// use core::time::Duration;
+/// use psolo_core::board::Board;
+/// use psolo_core::svg;
+///
+/// let board = Board::new("XXOXO").unwrap();
+/// let moves = [((0, 0), (2, 0)), ((2, 0), (4, 0))];
+/// let drawing = svg::animation(&board, &moves, Duration::from_secs(1)).unwrap();
+/// assert_eq!(drawing.matches("<animate ").count(), 3);
+/// assert_eq!(svg::animation(&board, &moves[1..], Duration::from_secs(1)), Err(0));
+/// ```
+pub fn animation(start: &Board, moves: &[Move], step: Duration) -> Result<String, usize> {
+    let (width, height) = diagram_size(start);
+    let mut out = header(width + 2 * MARGIN, height + CAPTION + 2 * MARGIN);
+    out.push_str(&arrow_marker());
+
+    // Frames are stacked and each is made visible in turn: the timeline
+    // has one slot per frame, plus two more for the final position
+    let slots = moves.len() as f64 + 3.;
+    let duration = step.as_secs_f64() * slots;
+    let mut board = start.clone();
+    for n in 0..=moves.len() {
+        let last = n.checked_sub(1).map(|index| (index, moves[index]));
+        if let Some((index, (src, dest))) = last {
+            if !board.is_valid_move(src, dest) {
+                return Err(index);
+            }
+            board.make_move(src, dest);
+        }
+        let (values, key_times) = if n == moves.len() {
+            ("0;1".to_string(), format!("0;{:.4}", n as f64 / slots))
+        } else if n == 0 {
+            ("1;0".to_string(), format!("0;{:.4}", 1. / slots))
+        } else {
+            (
+                "0;1;0".to_string(),
+                format!("0;{:.4};{:.4}", n as f64 / slots, (n + 1) as f64 / slots),
+            )
+        };
+        let _ = writeln!(
+            out,
+            r#"<g opacity="{}"><animate attributeName="opacity" values="{}" keyTimes="{}" calcMode="discrete" dur="{:.3}s" repeatCount="indefinite"/>"#,
+            if n == 0 { 1 } else { 0 },
+            values,
+            key_times,
+            duration
+        );
+        frame(&mut out, &board, last, MARGIN, MARGIN);
+        out.push_str("</g>\n");
+    }
+    out.push_str("</svg>\n");
+    Ok(out)
+}
+
+/// A single diagram of `start` where each of `moves` is drawn as an arrow
+/// from the peg to its destination, numbered in the order of play.
+///
+/// Fails with the index of the first move that isn't legal.
+pub fn annotated_solution(start: &Board, moves: &[Move]) -> Result<String, usize> {
+    let mut board = start.clone();
+    for (index, &(src, dest)) in moves.iter().enumerate() {
+        if !board.is_valid_move(src, dest) {
+            return Err(index);
+        }
+        board.make_move(src, dest);
+    }
+
+    let (width, height) = diagram_size(start);
+    let mut out = header(width + 2 * MARGIN, height + 2 * MARGIN);
+    out.push_str(&arrow_marker());
+    diagram(&mut out, start, MARGIN, MARGIN);
+    for (n, &mv) in moves.iter().enumerate() {
+        arrow(&mut out, mv, MARGIN, MARGIN);
+        // The number sits on the jumped peg, a little off the line so
+        // that the arrows crossing there don't hide it
+        let over = (((mv.0).0 + (mv.1).0) / 2, ((mv.0).1 + (mv.1).1) / 2);
+        let (cx, cy) = cell_center(over, MARGIN, MARGIN);
+        let _ = writeln!(
+            out,
+            r#"<text x="{}" y="{}" font-family="sans-serif" font-size="13" font-weight="bold" fill="{}" stroke="white" stroke-width="3" paint-order="stroke">{}</text>"#,
+            cx + 4,
+            cy - 4,
+            ARROW_COLOR,
+            n + 1
+        );
+    }
+    out.push_str("</svg>\n");
+    Ok(out)
+}
+
+fn header(width: u32, height: u32) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n\
+         <rect width=\"{0}\" height=\"{1}\" fill=\"white\"/>\n",
+        width, height
+    )
+}
+
+fn arrow_marker() -> String {
+    format!(
+        "<defs><marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"8\" refY=\"5\" \
+         markerWidth=\"5\" markerHeight=\"5\" orient=\"auto\">\
+         <path d=\"M0,0 L10,5 L0,10 z\" fill=\"{}\"/></marker></defs>\n",
+        ARROW_COLOR
+    )
+}
+
+fn diagram_size(board: &Board) -> (u32, u32) {
+    (board.width() * CELL, board.height() * CELL)
+}
+
+/// Center of the cell `(i, j)` of a diagram whose top left corner is on
+/// `(x, y)`.
+fn cell_center((i, j): (u32, u32), x: u32, y: u32) -> (u32, u32) {
+    (x + i * CELL + CELL / 2, y + j * CELL + CELL / 2)
+}
+
+fn diagram(out: &mut String, board: &Board, x: u32, y: u32) {
+    for j in 0..board.height() {
+        for i in 0..board.width() {
+            let cell = board.get_cell(i, j);
+            if cell == Cell::Unusable {
+                continue;
+            }
+            let _ = writeln!(
+                out,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+                x + i * CELL,
+                y + j * CELL,
+                CELL,
+                CELL,
+                BOARD_COLOR
+            );
+            let (cx, cy) = cell_center((i, j), x, y);
+            let _ = match cell {
+                Cell::Peg => writeln!(
+                    out,
+                    r#"<circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+                    cx,
+                    cy,
+                    CELL * 3 / 10,
+                    PEG_COLOR
+                ),
+                _ => writeln!(
+                    out,
+                    r#"<circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+                    cx,
+                    cy,
+                    CELL / 8,
+                    HOLE_COLOR
+                ),
+            };
+        }
+    }
+}
+
+/// A diagram of `board` with its caption, showing the move that led to it
+/// (if any, with its index) and the peg this move removed.
+fn frame(out: &mut String, board: &Board, last: Option<(usize, Move)>, x: u32, y: u32) {
+    let (width, height) = diagram_size(board);
+    diagram(out, board, x, y);
+    let caption = match last {
+        Some((index, (src, dest))) => {
+            let over = ((src.0 + dest.0) / 2, (src.1 + dest.1) / 2);
+            let (cx, cy) = cell_center(over, x, y);
+            let _ = writeln!(
+                out,
+                r#"<circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+                cx,
+                cy,
+                CELL * 3 / 10,
+                JUMPED_COLOR
+            );
+            arrow(out, (src, dest), x, y);
+            format!("{}. {}", index + 1, notation::format_move((src, dest)))
+        }
+        None => "Start".to_string(),
+    };
+    let _ = writeln!(
+        out,
+        r#"<text x="{}" y="{}" font-family="sans-serif" font-size="16" text-anchor="middle">{}</text>"#,
+        x + width / 2,
+        y + height + CAPTION - 6,
+        caption
+    );
+}
+
+fn arrow(out: &mut String, (src, dest): Move, x: u32, y: u32) {
+    let (x1, y1) = cell_center(src, x, y);
+    let (x2, y2) = cell_center(dest, x, y);
+    let _ = writeln!(
+        out,
+        r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}" stroke-width="4" marker-end="url(#arrow)"/>"#,
+        x1, y1, x2, y2, ARROW_COLOR
+    );
+}
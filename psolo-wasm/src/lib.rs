@@ -0,0 +1,283 @@
+//! The Peg Solitaire engine of `psolo-core` for JavaScript, without the
+//! game window: built with `wasm-pack build psolo-wasm`, it gives a `Game`
+//! class to play on a board and a `Search` class to look for a solution.
+//!
+//! Cells are given by their column and row, from the top left corner, and
+//! moves are written in notation (such as `d2-d4`) when they are text.
+//! Lists of moves are either text, moves being separated by spaces, or
+//! flat arrays of numbers, four for each move: the column and row of the
+//! peg, then of its destination.
+//!
+//! Outside of WebAssembly, the same types can be used from Rust, errors
+//! then being strings:
+//!
+//! ```
+//! use psolo_wasm::Game;
+//!
+//! let mut game = Game::new("english").unwrap();
+//! assert_eq!(game.pegs(), 32);
+//! assert_eq!(game.legal_moves(), vec![1, 3, 3, 3, 3, 1, 3, 3, 3, 5, 3, 3, 5, 3, 3, 3]);
+//! assert!(game.play_notation("d2-d4"));
+//! assert!(game.undo());
+//! assert_eq!(game.code(), "english.777X774");
+//! ```
+#![warn(missing_docs)]
+
+use psolo_core::board::{Board, Move};
+use psolo_core::cell::Cell;
+use psolo_core::record::Record;
+use psolo_core::solver::{Outcome, Solver};
+use psolo_core::{boards, code, notation};
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::*;
+
+/// Error given to JavaScript as an exception.
+#[cfg(target_arch = "wasm32")]
+pub type Error = JsValue;
+
+/// Error message.
+#[cfg(not(target_arch = "wasm32"))]
+pub type Error = String;
+
+fn error(message: &str) -> Error {
+    message.into()
+}
+
+fn format_moves(moves: &[Move]) -> String {
+    let moves: Vec<String> = moves.iter().map(|&mv| notation::format_move(mv)).collect();
+    moves.join(" ")
+}
+
+/// A game on a board: its position and the moves played since its start.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub struct Game {
+    board: Board,
+    record: Record,
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Game {
+    /// Start a game on one of the shipped boards, given by its name, or on
+    /// a shape written with `X` (peg), `O` (hole) and spaces.
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(constructor))]
+    pub fn new(name_or_shape: &str) -> Result<Game, Error> {
+        boards::load(name_or_shape)
+            .map(Game::from_board)
+            .map_err(|e| {
+                error(&format!(
+                    "Unable to make a board from this name or shape: {}",
+                    e
+                ))
+            })
+    }
+
+    /// Start a game from the position written in a code made by `code`. A
+    /// code of a board other than the shipped ones needs the shape of this
+    /// board (whatever its pegs); for the shipped ones, the shape may be an
+    /// empty string.
+    ///
+    /// ```
+    /// use psolo_wasm::Game;
+    ///
+    /// assert!(Game::from_code("english.777X774", "").is_ok());
+    /// let code = Game::new("XXO").unwrap().code();
+    /// assert!(Game::from_code(&code, "OOO").is_ok());
+    /// assert!(Game::from_code(&code, "").is_err());
+    /// ```
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = fromCode))]
+    pub fn from_code(position_code: &str, shape: &str) -> Result<Game, Error> {
+        let layouts = if shape.trim().is_empty() {
+            Vec::new()
+        } else {
+            vec![boards::load(shape)
+                .map_err(|e| error(&format!("Unable to make a board from this shape: {}", e)))?]
+        };
+        code::decode(position_code, &layouts)
+            .map(Game::from_board)
+            .map_err(|e| error(&format!("Unable to read this position code: {}", e)))
+    }
+
+    /// Number of columns.
+    pub fn width(&self) -> u32 {
+        self.board.width()
+    }
+
+    /// Number of rows.
+    pub fn height(&self) -> u32 {
+        self.board.height()
+    }
+
+    /// Content of the cell `(i, j)`: 0 for a peg, 1 for a hole and 2 for a
+    /// cell that can't be used (including cells outside of the board).
+    pub fn cell(&self, i: u32, j: u32) -> u8 {
+        if i >= self.board.width() || j >= self.board.height() {
+            return Cell::Unusable as u8;
+        }
+        self.board.get_cell(i, j) as u8
+    }
+
+    /// Content of every cell, row by row, as given by `cell`.
+    pub fn cells(&self) -> Vec<u8> {
+        (0..self.board.height())
+            .flat_map(|j| (0..self.board.width()).map(move |i| (i, j)))
+            .map(|(i, j)| self.cell(i, j))
+            .collect()
+    }
+
+    /// The position as a shape, one line per row.
+    pub fn shape(&self) -> String {
+        self.board.to_string()
+    }
+
+    /// Number of pegs left.
+    pub fn pegs(&self) -> u32 {
+        self.board.count_peg() as u32
+    }
+
+    /// Whether no move is left, the game being won when a single peg is.
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = isOver))]
+    pub fn is_over(&self) -> bool {
+        !self.board.has_valid_move_left()
+    }
+
+    /// Every legal move, four numbers for each.
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = legalMoves))]
+    pub fn legal_moves(&self) -> Vec<u32> {
+        self.board
+            .legal_moves()
+            .iter()
+            .flat_map(|&((i_src, j_src), (i_dest, j_dest))| vec![i_src, j_src, i_dest, j_dest])
+            .collect()
+    }
+
+    /// Play the peg on `(i_src, j_src)` to `(i_dest, j_dest)`, if this
+    /// move is legal; whether it was played.
+    pub fn play(&mut self, i_src: u32, j_src: u32, i_dest: u32, j_dest: u32) -> bool {
+        let (src, dest) = ((i_src, j_src), (i_dest, j_dest));
+        if !self.board.is_valid_move(src, dest) {
+            return false;
+        }
+        self.board.make_move(src, dest);
+        self.record.moves.push((src, dest));
+        true
+    }
+
+    /// Play a move written in notation, if it is legal; whether it was
+    /// played.
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = playNotation))]
+    pub fn play_notation(&mut self, mv: &str) -> bool {
+        match notation::parse_move(mv) {
+            Ok(((i_src, j_src), (i_dest, j_dest))) => self.play(i_src, j_src, i_dest, j_dest),
+            Err(_) => false,
+        }
+    }
+
+    /// Take back the last move played; whether there was one.
+    pub fn undo(&mut self) -> bool {
+        match self.record.moves.pop() {
+            Some((src, dest)) => {
+                self.board.undo_move(src, dest);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Go back to the start of the game.
+    pub fn restart(&mut self) {
+        self.board = self.record.start.clone();
+        self.record.moves.clear();
+    }
+
+    /// Moves played since the start, as text.
+    pub fn moves(&self) -> String {
+        format_moves(&self.record.moves)
+    }
+
+    /// The game as a record, in the text form read by `psolo-cli check`.
+    pub fn record(&self) -> String {
+        self.record.to_string()
+    }
+
+    /// Code of the position, as read by `fromCode`.
+    pub fn code(&self) -> String {
+        code::encode(&self.board)
+    }
+
+    /// A search for moves leaving a single peg from the position, which
+    /// gives up after ruling out two million positions.
+    ///
+    /// Fails if the board has more than 128 usable cells.
+    pub fn search(&self) -> Result<Search, Error> {
+        Solver::new(&self.board)
+            .map(|solver| Search {
+                solver,
+                result: None,
+            })
+            .map_err(|e| error(&format!("Unable to solve the board: {}", e)))
+    }
+}
+
+impl Game {
+    fn from_board(board: Board) -> Self {
+        Game {
+            record: Record::new(board.clone()),
+            board,
+        }
+    }
+}
+
+/// A search for a solution, done a few positions at a time (for instance
+/// on each animation frame) so that the page keeps responding. It ends
+/// either with a solution, with the proof that there is none, or by giving
+/// up on the larger boards.
+///
+/// ```
+/// use psolo_wasm::Game;
+///
+/// let mut search = Game::new("XXOX").unwrap().search().unwrap();
+/// while !search.step(1000) {}
+/// assert!(search.found());
+/// assert_eq!(search.solution(), Some("a1-c1 d1-b1".to_string()));
+///
+/// let mut search = Game::new("european").unwrap().search().unwrap();
+/// while !search.step(1000) {}
+/// assert!(!search.found() && !search.gave_up());
+/// ```
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub struct Search {
+    solver: Solver,
+    result: Option<Outcome>,
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+impl Search {
+    /// Try at most `budget` jumps; whether the search is over.
+    pub fn step(&mut self, budget: u32) -> bool {
+        if self.result.is_none() {
+            self.result = self.solver.step(budget as usize);
+        }
+        self.result.is_some()
+    }
+
+    /// Whether the search is over and found a solution.
+    pub fn found(&self) -> bool {
+        matches!(self.result, Some(Outcome::Solved(_)))
+    }
+
+    /// Whether the search is over because it gave up, without finding a
+    /// solution nor proving that there is none.
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = gaveUp))]
+    pub fn gave_up(&self) -> bool {
+        matches!(self.result, Some(Outcome::GaveUp))
+    }
+
+    /// The moves found, as text, once the search is over.
+    pub fn solution(&self) -> Option<String> {
+        match &self.result {
+            Some(Outcome::Solved(moves)) => Some(format_moves(moves)),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use std::f32::consts::PI;
+use std::time::Duration;
+
+use instant::Instant;
+use quicksilver::geom::Vector;
+
+use psolo_core::board::Move;
+
+// Height of the arc described by a jumping peg, relative to the length of the jump
+const JUMP_HEIGHT: f32 = 0.4;
+
+// The progress of an animation started at some point and lasting `duration`
+pub struct Tween {
+    started: Instant,
+    duration: Duration,
+}
+
+impl Tween {
+    pub fn new(duration: Duration) -> Self {
+        Tween {
+            started: Instant::now(),
+            duration,
+        }
+    }
+
+    // Progress between 0 and 1, slower at both ends
+    pub fn progress(&self) -> f32 {
+        if self.duration.as_millis() == 0 {
+            return 1.;
+        }
+        let t = (self.started.elapsed().as_secs_f32() / self.duration.as_secs_f32()).min(1.);
+        t * t * (3. - 2. * t)
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.started.elapsed() >= self.duration
+    }
+}
+
+pub fn lerp(from: Vector, to: Vector, t: f32) -> Vector {
+    from + (to - from) * t
+}
+
+// A peg jumping over another one, which shrinks until it disappears
+pub struct Jump {
+    pub mv: Move,
+    pub over: (u32, u32),
+    pub tween: Tween,
+    // Where the peg was dropped, when it was dragged rather than picked
+    dropped_at: Option<Vector>,
+}
+
+impl Jump {
+    pub fn new((src, dest): Move, duration: Duration) -> Self {
+        Jump {
+            mv: (src, dest),
+            over: ((src.0 + dest.0) / 2, (src.1 + dest.1) / 2),
+            tween: Tween::new(duration),
+            dropped_at: None,
+        }
+    }
+
+    pub fn dropped(mv: Move, at: Vector, duration: Duration) -> Self {
+        Jump {
+            dropped_at: Some(at),
+            ..Jump::new(mv, duration)
+        }
+    }
+
+    // Position of the jumping peg, on an arc going from the center of the
+    // source cell (or from where it was dropped) to the center of the destination
+    pub fn peg_position(&self, src: Vector, to: Vector) -> Vector {
+        let from = self.dropped_at.unwrap_or(src);
+        let t = self.tween.progress();
+        let height = (to - from).len() * JUMP_HEIGHT;
+        lerp(from, to, t) - Vector::new(0., height * (PI * t).sin())
+    }
+
+    // Size of the jumped peg, relative to its usual size
+    pub fn captured_scale(&self) -> f32 {
+        1. - self.tween.progress()
+    }
+}
+
+// A peg dropped where it can't go, going back to the cell it was taken from
+pub struct DropBack {
+    pub src: (u32, u32),
+    pub tween: Tween,
+    from: Vector,
+}
+
+impl DropBack {
+    pub fn new(src: (u32, u32), from: Vector, duration: Duration) -> Self {
+        DropBack {
+            src,
+            tween: Tween::new(duration),
+            from,
+        }
+    }
+
+    pub fn peg_position(&self, to: Vector) -> Vector {
+        lerp(self.from, to, self.tween.progress())
+    }
+}
+
+pub enum Animation {
+    Jump(Jump),
+    DropBack(DropBack),
+}
+
+impl Animation {
+    pub fn is_done(&self) -> bool {
+        match self {
+            Animation::Jump(jump) => jump.tween.is_done(),
+            Animation::DropBack(drop_back) => drop_back.tween.is_done(),
+        }
+    }
+
+    // Cell that must be drawn empty while the animation plays
+    pub fn hidden_cell(&self) -> (u32, u32) {
+        match self {
+            Animation::Jump(jump) => jump.mv.1,
+            Animation::DropBack(drop_back) => drop_back.src,
+        }
+    }
+}
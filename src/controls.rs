@@ -0,0 +1,118 @@
+use quicksilver::blinds::event::{GamepadAxis, GamepadButton, Key};
+use quicksilver::input::Event;
+
+// How far a stick has to be pushed to move the cursor, and released to move it again
+const STICK_PUSHED: f32 = 0.5;
+const STICK_RELEASED: f32 = 0.3;
+
+// What the player asks for, whatever the device used
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Command {
+    Up,
+    Down,
+    Left,
+    Right,
+    Select,
+    Cancel,
+    Undo,
+    Restart,
+    Save,
+    Copy,
+    ToggleAids,
+    Pause,
+}
+
+impl Command {
+    // Direction of the cursor move asked for, as a step on the columns and on the rows
+    pub fn direction(self) -> Option<(i64, i64)> {
+        match self {
+            Command::Up => Some((0, -1)),
+            Command::Down => Some((0, 1)),
+            Command::Left => Some((-1, 0)),
+            Command::Right => Some((1, 0)),
+            _ => None,
+        }
+    }
+}
+
+// Keys are read on a QWERTY layout; [S] saves when [Ctrl] is held, as it
+// otherwise moves the cursor down
+pub fn from_key(key: Key, ctrl: bool) -> Option<Command> {
+    match key {
+        Key::S if ctrl => Some(Command::Save),
+        Key::C if ctrl => Some(Command::Copy),
+        Key::Up | Key::W => Some(Command::Up),
+        Key::Down | Key::S => Some(Command::Down),
+        Key::Left | Key::A => Some(Command::Left),
+        Key::Right | Key::D => Some(Command::Right),
+        Key::Space | Key::Return => Some(Command::Select),
+        Key::Escape => Some(Command::Cancel),
+        Key::U => Some(Command::Undo),
+        Key::R => Some(Command::Restart),
+        Key::H => Some(Command::ToggleAids),
+        Key::P => Some(Command::Pause),
+        _ => None,
+    }
+}
+
+pub fn from_button(button: GamepadButton) -> Option<Command> {
+    match button {
+        GamepadButton::DPadUp => Some(Command::Up),
+        GamepadButton::DPadDown => Some(Command::Down),
+        GamepadButton::DPadLeft => Some(Command::Left),
+        GamepadButton::DPadRight => Some(Command::Right),
+        GamepadButton::South => Some(Command::Select),
+        GamepadButton::East => Some(Command::Cancel),
+        GamepadButton::West => Some(Command::Undo),
+        GamepadButton::North => Some(Command::Restart),
+        GamepadButton::Select => Some(Command::ToggleAids),
+        GamepadButton::Start => Some(Command::Pause),
+        _ => None,
+    }
+}
+
+// Turns the keyboard and gamepad events into commands, keeping track of
+// the direction the left stick is pushed to so that holding it moves the
+// cursor only once
+#[derive(Default)]
+pub struct Controls {
+    stick: (i64, i64),
+}
+
+impl Controls {
+    pub fn command(&mut self, event: &Event, ctrl: bool) -> Option<Command> {
+        match event {
+            Event::KeyboardInput(k_ev) if k_ev.is_down() => from_key(k_ev.key(), ctrl),
+            Event::GamepadButton(b_ev) if b_ev.is_down() => from_button(b_ev.button()),
+            Event::GamepadAxis(a_ev) => self.stick_moved(a_ev.axis(), a_ev.value()),
+            _ => None,
+        }
+    }
+
+    fn stick_moved(&mut self, axis: GamepadAxis, value: f32) -> Option<Command> {
+        let direction = if value > STICK_PUSHED {
+            1
+        } else if value < -STICK_PUSHED {
+            -1
+        } else if value.abs() < STICK_RELEASED {
+            0
+        } else {
+            return None;
+        };
+        // The vertical axis points up
+        let (previous, command) = match (axis, direction) {
+            (GamepadAxis::LeftStickX, 1) => (&mut self.stick.0, Some(Command::Right)),
+            (GamepadAxis::LeftStickX, -1) => (&mut self.stick.0, Some(Command::Left)),
+            (GamepadAxis::LeftStickX, _) => (&mut self.stick.0, None),
+            (GamepadAxis::LeftStickY, 1) => (&mut self.stick.1, Some(Command::Up)),
+            (GamepadAxis::LeftStickY, -1) => (&mut self.stick.1, Some(Command::Down)),
+            (GamepadAxis::LeftStickY, _) => (&mut self.stick.1, None),
+            _ => return None,
+        };
+        if *previous == direction {
+            return None;
+        }
+        *previous = direction;
+        command
+    }
+}
@@ -0,0 +1,335 @@
+use std::time::Duration;
+
+use instant::Instant;
+use quicksilver::{
+    geom::{Circle, Rectangle, Vector},
+    graphics::Color,
+    Graphics,
+};
+
+use psolo_core::board::Board;
+use psolo_core::record::Record;
+use psolo_core::selection;
+
+use crate::registry;
+use crate::save;
+use crate::stats;
+use crate::theme::Theme;
+
+pub struct ScreenBoard {
+    pub board: Board,
+    pub record: Record,
+    pub played_before: Duration,
+    pub resumed_at: Instant,
+    pub time_limit: Option<Duration>,
+    pub finished: bool,
+    // The clock is stopped while the game is paused
+    pub paused: bool,
+    pub cursor: (u32, u32),
+    pub cell_size: f32,
+    pub cell_margin: f32,
+    pub d_cell_peg_size: f32,
+    pub d_cell_hole_size: f32,
+    pub hole_size: f32,
+    pub peg_size: f32,
+    pub cell_with_margin: f32,
+    pub board_size: (f32, f32),
+    pub board_margin_top: f32,
+    pub board_margin_left: f32,
+}
+
+impl ScreenBoard {
+    pub fn new(board: Board) -> Self {
+        // The sizes are set by `fit`, once the size of the window is known
+        ScreenBoard {
+            record: Record::new(board.clone()),
+            cursor: selection::first_cell(&board).unwrap_or((0, 0)),
+            board,
+            played_before: Duration::from_secs(0),
+            resumed_at: Instant::now(),
+            time_limit: None,
+            finished: false,
+            paused: false,
+            cell_size: 0.,
+            cell_margin: 0.,
+            d_cell_peg_size: 0.,
+            d_cell_hole_size: 0.,
+            board_size: (0., 0.),
+            hole_size: 0.,
+            peg_size: 0.,
+            cell_with_margin: 0.,
+            board_margin_top: 0.,
+            board_margin_left: 0.,
+        }
+    }
+
+    // Size the cells so that the board fills `area` (without cells larger
+    // than `max_cell_size`) and center it there
+    pub fn fit(&mut self, area: Rectangle, max_cell_size: f32) {
+        let cell_with_margin = (area.width() / self.board.width() as f32)
+            .min(area.height() / self.board.height() as f32)
+            .min(max_cell_size);
+        // Same proportions as the original 50 pixels cells with 2 pixels margins
+        self.cell_margin = cell_with_margin * 2. / 52.;
+        self.cell_size = cell_with_margin - self.cell_margin;
+        self.d_cell_peg_size = self.cell_size * 3. / 50.;
+        self.d_cell_hole_size = self.cell_size * 6. / 50.;
+        self.hole_size = (self.cell_size / 2.) - self.d_cell_hole_size;
+        self.peg_size = (self.cell_size / 2.) - self.d_cell_peg_size;
+        self.cell_with_margin = cell_with_margin;
+        self.board_size = (
+            self.board.width() as f32 * cell_with_margin,
+            self.board.height() as f32 * cell_with_margin,
+        );
+        self.board_margin_left = area.x() + (area.width() - self.board_size.0) / 2.;
+        self.board_margin_top = area.y() + (area.height() - self.board_size.1) / 2.;
+    }
+
+    // Same as `new`, but with the game of a saved record
+    pub fn resume(saved: save::SavedGame) -> Option<Self> {
+        let board = saved.record.replay().ok()?;
+        let mut sb = ScreenBoard::new(board);
+        sb.played_before = saved.record.elapsed.unwrap_or_default();
+        sb.record = saved.record;
+        sb.time_limit = saved.time_limit;
+        Some(sb)
+    }
+
+    // Time spent on this game, including the time played before it was saved.
+    // The clock stops once the game is over, and while it is paused.
+    pub fn elapsed(&self) -> Duration {
+        match self.record.elapsed {
+            Some(elapsed) if self.finished => elapsed,
+            _ if self.paused => self.played_before,
+            _ => self.played_before + self.resumed_at.elapsed(),
+        }
+    }
+
+    pub fn pause(&mut self) {
+        if !self.paused {
+            self.played_before = self.elapsed();
+            self.paused = true;
+        }
+    }
+
+    pub fn unpause(&mut self) {
+        if self.paused {
+            self.resumed_at = Instant::now();
+            self.paused = false;
+        }
+    }
+
+    // A new game on the same board, with the same time limit
+    pub fn restarted(&self) -> Self {
+        let mut sb = ScreenBoard::new(self.record.start.clone());
+        sb.time_limit = self.time_limit;
+        sb
+    }
+
+    // Time left before the end of a timed challenge
+    pub fn remaining(&self) -> Option<Duration> {
+        self.time_limit
+            .map(|limit| limit.saturating_sub(self.elapsed()))
+    }
+
+    pub fn is_time_up(&self) -> bool {
+        self.remaining() == Some(Duration::from_secs(0))
+    }
+
+    pub fn finish(&mut self) {
+        self.record.elapsed = Some(self.elapsed());
+        self.finished = true;
+    }
+
+    // Go back to a game that is over, so that its last moves can be undone
+    pub fn reopen(&mut self) {
+        if self.finished {
+            self.played_before = self.elapsed();
+            self.resumed_at = Instant::now();
+            self.paused = false;
+            self.finished = false;
+        }
+    }
+
+    pub fn play(&mut self, src: (u32, u32), dest: (u32, u32)) {
+        self.board.make_move(src, dest);
+        self.record.moves.push((src, dest));
+    }
+
+    // Take back the last move, unless the game is over
+    pub fn undo(&mut self) {
+        if self.finished {
+            return;
+        }
+        if let Some((src, dest)) = self.record.moves.pop() {
+            self.board.undo_move(src, dest);
+            self.save();
+        }
+    }
+
+    pub fn save(&mut self) {
+        // A finished game can't be continued
+        if !self.finished {
+            self.record.elapsed = Some(self.elapsed());
+            save::save_game(&self.record, self.time_limit);
+        }
+    }
+
+    pub fn cell_rect(&self, i: u32, j: u32) -> Rectangle {
+        Rectangle::new(
+            Vector::new(
+                self.board_margin_left + i as f32 * self.cell_with_margin,
+                self.board_margin_top + j as f32 * self.cell_with_margin,
+            ),
+            Vector::new(self.cell_size, self.cell_size),
+        )
+    }
+
+    pub fn cell_center(&self, i: u32, j: u32) -> Vector {
+        Vector::new(
+            self.board_margin_left + i as f32 * self.cell_with_margin + self.cell_size / 2.,
+            self.board_margin_top + j as f32 * self.cell_with_margin + self.cell_size / 2.,
+        )
+    }
+
+    pub fn get_row_col_cell_clicked(&self, position: Vector) -> Option<(u32, u32)> {
+        let mut x = position.x;
+        let mut y = position.y;
+        x -= self.board_margin_left;
+        y -= self.board_margin_top;
+        x /= self.cell_size + self.cell_margin;
+        y /= self.cell_size + self.cell_margin;
+        x = x.floor();
+        y = y.floor();
+        if x < self.board.width() as f32 && y < self.board.height() as f32 && x >= 0. && y >= 0. {
+            Some((x as u32, y as u32))
+        } else {
+            None
+        }
+    }
+}
+
+// Stop the clock of a game that is over and count it in the statistics,
+// telling if it sets a new personal best
+pub fn end_game(
+    sb: &mut ScreenBoard,
+    stats: &mut stats::Stats,
+    registry: &[registry::BoardEntry],
+) -> bool {
+    sb.finish();
+    save::clear_saved_game();
+    let new_best = record_result(sb, stats, registry);
+    stats.save();
+    new_best
+}
+
+// Count the result of a game that is over, which was counted as played
+// when it started
+fn record_result(
+    sb: &ScreenBoard,
+    stats: &mut stats::Stats,
+    registry: &[registry::BoardEntry],
+) -> bool {
+    match registry::find(registry, &sb.record.start) {
+        // A game lost because the time was up is only counted as played:
+        // the pegs left then don't tell how well it went
+        Some(_) if sb.is_time_up() => false,
+        Some(entry) => stats.record_game(
+            &entry.key,
+            sb.board.count_peg(),
+            sb.record.move_count(),
+            sb.elapsed(),
+        ),
+        None => false,
+    }
+}
+
+// Count a game that starts in the statistics, so that it is counted as
+// played even when it is left unfinished
+pub fn count_game(sb: &ScreenBoard, stats: &mut stats::Stats, registry: &[registry::BoardEntry]) {
+    if let Some(entry) = registry::find(registry, &sb.record.start) {
+        stats.start_game(&entry.key);
+        stats.save();
+    }
+}
+
+// The classic rating of a game by the number of pegs left on the board
+pub fn rating(pegs_left: usize) -> &'static str {
+    match pegs_left {
+        0 | 1 => "Genius",
+        2 => "Excellent",
+        3 => "Very good",
+        4 => "Good",
+        _ => "Average",
+    }
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+pub fn make_peg(
+    sb: &ScreenBoard,
+    i: u32,
+    j: u32,
+    color_rect: Color,
+    color_circle: Color,
+    theme: &Theme,
+    g: &mut Graphics,
+) {
+    theme.draw_cell(&sb.cell_rect(i, j), color_rect, g);
+    theme.draw_peg(sb.cell_center(i, j), sb.peg_size, color_circle, g);
+}
+
+pub fn make_hole(sb: &ScreenBoard, i: u32, j: u32, theme: &Theme, g: &mut Graphics) {
+    theme.draw_cell(&sb.cell_rect(i, j), theme.cell, g);
+    g.fill_circle(&Circle::new(sb.cell_center(i, j), sb.hole_size), theme.hole);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finished_game(
+        registry: &[registry::BoardEntry],
+        time_limit: Option<Duration>,
+    ) -> ScreenBoard {
+        let mut sb = ScreenBoard::new(registry[0].board.clone());
+        sb.time_limit = time_limit;
+        sb.play((0, 0), (2, 0));
+        sb.finish();
+        sb
+    }
+
+    #[test]
+    fn time_up_is_only_a_loss() {
+        let registry = vec![registry::BoardEntry {
+            key: "line".to_string(),
+            title: "Line".to_string(),
+            board: Board::new("XXO").unwrap(),
+        }];
+        let mut stats = stats::Stats::default();
+
+        // Won on the board, but after the time was up
+        stats.start_game("line");
+        let sb = finished_game(&registry, Some(Duration::from_secs(0)));
+        assert!(sb.is_time_up());
+        assert!(!record_result(&sb, &mut stats, &registry));
+        let line = stats.get("line");
+        assert_eq!((line.played, line.wins), (1, 0));
+        assert_eq!(line.fewest_pegs, None);
+        assert_eq!(line.fewest_moves, None);
+        assert_eq!(line.fastest, None);
+
+        // The same game within the time counts as a win
+        stats.start_game("line");
+        let sb = finished_game(&registry, Some(Duration::from_secs(600)));
+        assert!(!sb.is_time_up());
+        record_result(&sb, &mut stats, &registry);
+        let line = stats.get("line");
+        assert_eq!((line.played, line.wins), (2, 1));
+        assert_eq!(line.fewest_pegs, Some(1));
+        assert_eq!(line.fewest_moves, Some(1));
+    }
+}
@@ -0,0 +1,92 @@
+use quicksilver::geom::{Rectangle, Vector};
+
+// Positions on screen are given as if the window was 600x600, then scaled
+// to the actual size of the window: centered, or anchored to one of its
+// sides so that the extra room goes to the board
+const REFERENCE_SIZE: f32 = 600.;
+
+// Largest size of a cell (margin included) in the reference window
+const MAX_CELL_SIZE: f32 = 60.;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Layout {
+    size: Vector,
+    scale: f32,
+}
+
+impl Layout {
+    pub fn new(size: Vector) -> Self {
+        Layout {
+            size,
+            scale: (size.x.min(size.y) / REFERENCE_SIZE).max(0.1),
+        }
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    // A length of the reference window
+    pub fn len(&self, length: f32) -> f32 {
+        length * self.scale
+    }
+
+    // Size of a font, rounded as fonts are rendered at whole pixel sizes
+    pub fn font_size(&self, size: f32) -> f32 {
+        (size * self.scale).round().max(1.)
+    }
+
+    fn offset(&self) -> Vector {
+        (self.size - Vector::new(REFERENCE_SIZE, REFERENCE_SIZE) * self.scale) / 2.
+    }
+
+    // A point of the reference window, in the middle of the window
+    pub fn point(&self, x: f32, y: f32) -> Vector {
+        self.offset() + Vector::new(x, y) * self.scale
+    }
+
+    // A rectangle of the reference window, in the middle of the window
+    pub fn rect(&self, (x, y, width, height): (f32, f32, f32, f32)) -> (f32, f32, f32, f32) {
+        let top_left = self.point(x, y);
+        (top_left.x, top_left.y, self.len(width), self.len(height))
+    }
+
+    // A point of the reference window, centered horizontally and kept at
+    // the same distance from the bottom of the window
+    pub fn bottom(&self, x: f32, y: f32) -> Vector {
+        Vector::new(
+            self.point(x, y).x,
+            self.size.y - (REFERENCE_SIZE - y) * self.scale,
+        )
+    }
+
+    // Points of the reference window, kept at the same distance from a corner of the window
+    pub fn top_left(&self, x: f32, y: f32) -> Vector {
+        Vector::new(x, y) * self.scale
+    }
+
+    pub fn top_right(&self, x: f32, y: f32) -> Vector {
+        Vector::new(
+            self.size.x - (REFERENCE_SIZE - x) * self.scale,
+            y * self.scale,
+        )
+    }
+
+    pub fn bottom_left(&self, x: f32, y: f32) -> Vector {
+        Vector::new(
+            x * self.scale,
+            self.size.y - (REFERENCE_SIZE - y) * self.scale,
+        )
+    }
+
+    // Where the board is drawn: the whole window but the header, the footer and a margin
+    pub fn board_area(&self) -> Rectangle {
+        let top_left = self.top_left(20., 100.);
+        let bottom_right = self.size - self.top_left(20., 95.);
+        Rectangle::new(top_left, bottom_right - top_left)
+    }
+
+    pub fn max_cell_size(&self) -> f32 {
+        self.len(MAX_CELL_SIZE)
+    }
+}
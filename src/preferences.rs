@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use quicksilver::saving::{load_raw, save_raw, Location};
+
+use crate::save::APP_NAME;
+
+const PREFERENCES: &str = "preferences";
+
+// Preferences are saved with one setting per line, its name followed by a
+// space and its value. Unknown or missing settings keep their default value.
+
+#[derive(Debug, Clone)]
+pub struct Preferences {
+    // Name of the theme, as given in the themes file
+    pub theme: String,
+    pub show_aids: bool,
+    // Duration of the animation of a move, zero disabling animations
+    pub animation_duration: Duration,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Preferences {
+            theme: "Classic".to_string(),
+            show_aids: true,
+            animation_duration: Duration::from_millis(300),
+        }
+    }
+}
+
+impl Preferences {
+    pub fn load() -> Self {
+        let mut preferences = Preferences::default();
+        let text = load_raw(Location::Data, APP_NAME, PREFERENCES)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .unwrap_or_default();
+        for line in text.lines() {
+            match line.split_once(' ') {
+                Some(("theme", name)) => preferences.theme = name.to_string(),
+                Some(("hints", "on")) => preferences.show_aids = true,
+                Some(("hints", "off")) => preferences.show_aids = false,
+                Some(("animation", ms)) => {
+                    if let Ok(ms) = ms.parse() {
+                        preferences.animation_duration = Duration::from_millis(ms);
+                    }
+                }
+                _ => {}
+            }
+        }
+        preferences
+    }
+
+    pub fn save(&self) {
+        let text = format!(
+            "theme {}\nhints {}\nanimation {}\n",
+            self.theme,
+            if self.show_aids { "on" } else { "off" },
+            self.animation_duration.as_millis()
+        );
+        if let Err(e) = save_raw(Location::Data, APP_NAME, PREFERENCES, text.as_bytes()) {
+            quicksilver::log::warn!("Unable to save the preferences: {}", e);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use psolo_core::board::Board;
+use psolo_core::boards;
+
+// File of the `static` directory the extra boards are read from
+pub const BOARDS_FILE: &str = "boards.txt";
+
+// A board the user can pick in the menu
+#[derive(Debug, Clone)]
+pub struct BoardEntry {
+    // Name the statistics of the board are kept under, without spaces
+    pub key: String,
+    pub title: String,
+    pub board: Board,
+}
+
+// The boards shipped with the game, then the ones of a boards file (see
+// `static/boards.txt`), skipping those whose shape can't be read
+pub fn load(extra_boards: &str) -> Vec<BoardEntry> {
+    let mut entries: Vec<BoardEntry> = boards::BOARDS
+        .iter()
+        .map(|(name, shape)| BoardEntry {
+            key: name.to_string(),
+            title: format!("{}{} Board", name[..1].to_uppercase(), &name[1..]),
+            board: Board::new(shape).expect("Unable to make board from the provided string"),
+        })
+        .collect();
+
+    // Shapes start with a space as often as not, so lines are not trimmed
+    let mut title: Option<&str> = None;
+    let mut shape: Vec<&str> = Vec::new();
+    for line in extra_boards.lines().chain(std::iter::once("")) {
+        let header = line
+            .trim_end()
+            .strip_prefix('[')
+            .and_then(|l| l.strip_suffix(']'));
+        if line.starts_with('#') {
+            continue;
+        }
+        if header.is_none() && !line.trim().is_empty() {
+            shape.push(line);
+            continue;
+        }
+        // A new board or a blank line ends the shape of the previous board
+        if let Some(name) = title {
+            if !shape.is_empty() {
+                match boards::load(&shape.join("\n")) {
+                    Ok(board) => entries.push(BoardEntry {
+                        key: name.to_lowercase().replace(char::is_whitespace, "-"),
+                        title: name.to_string(),
+                        board,
+                    }),
+                    Err(e) => quicksilver::log::warn!("Unable to read the board '{}': {}", name, e),
+                }
+                title = None;
+            }
+        }
+        shape.clear();
+        if let Some(name) = header {
+            title = Some(name.trim());
+        }
+    }
+    entries
+}
+
+// The entry of the registry whose starting position is `start`
+pub fn find<'a>(registry: &'a [BoardEntry], start: &Board) -> Option<&'a BoardEntry> {
+    registry.iter().find(|entry| entry.board == *start)
+}
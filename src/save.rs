@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use quicksilver::saving::{load_raw, save_raw, Location};
+
+use psolo_core::record::Record;
+
+pub const APP_NAME: &str = "psolo-rs";
+const SAVED_GAME: &str = "saved_game";
+
+// The game is saved as the time limit of a challenge in seconds (or `-`
+// for a game without any) on the first line, followed by the record of the
+// game in its text form, elapsed time included. Since there is no way to
+// remove a saved profile, an empty file stands for "no saved game".
+
+pub struct SavedGame {
+    pub record: Record,
+    pub time_limit: Option<Duration>,
+}
+
+pub fn save_game(record: &Record, time_limit: Option<Duration>) {
+    let limit = time_limit.map_or_else(|| "-".to_string(), |limit| limit.as_secs().to_string());
+    let text = format!("{}\n{}", limit, record);
+    if let Err(e) = save_raw(Location::Data, APP_NAME, SAVED_GAME, text.as_bytes()) {
+        quicksilver::log::warn!("Unable to save the game: {}", e);
+    }
+}
+
+pub fn load_game() -> Option<SavedGame> {
+    let bytes = load_raw(Location::Data, APP_NAME, SAVED_GAME).ok()?;
+    let text = String::from_utf8(bytes).ok()?;
+    let mut parts = text.splitn(2, '\n');
+    let time_limit = match parts.next()?.trim() {
+        "-" => None,
+        limit => Some(Duration::from_secs(limit.parse().ok()?)),
+    };
+    let record: Record = parts.next()?.parse().ok()?;
+    // Don't offer to continue a game whose moves can't be played anymore
+    record.replay().ok()?;
+    Some(SavedGame { record, time_limit })
+}
+
+pub fn clear_saved_game() {
+    if let Err(e) = save_raw(Location::Data, APP_NAME, SAVED_GAME, &[]) {
+        quicksilver::log::warn!("Unable to clear the saved game: {}", e);
+    }
+}
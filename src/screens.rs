@@ -0,0 +1,1258 @@
+use std::time::Duration;
+
+use instant::Instant;
+use quicksilver::{
+    blinds::{event::Key, CursorIcon, MouseButton},
+    geom::{Circle, Rectangle, Vector},
+    graphics::FontRenderer,
+    input::Event,
+    Graphics, Window,
+};
+
+use psolo_core::board::{Board, Move};
+use psolo_core::cell::Cell;
+use psolo_core::code;
+use psolo_core::selection::{self, Selection};
+use psolo_core::solver::{Outcome, Solver};
+
+use crate::animation::{Animation, DropBack, Jump};
+use crate::controls::{Command, Controls};
+use crate::game::{self, format_duration, make_hole, make_peg, rating, ScreenBoard};
+use crate::layout::Layout;
+use crate::preferences::Preferences;
+use crate::registry::BoardEntry;
+use crate::save::{self, SavedGame};
+use crate::sharing;
+use crate::stats::{self, Stats};
+use crate::theme::Theme;
+use crate::ui::{Item, List, ListState};
+
+// Distance the mouse has to move, button pressed, to start dragging a peg
+const DRAG_THRESHOLD: f32 = 5.;
+
+// Time between two moves of a replay or of a solution
+const DEMO_STEP: Duration = Duration::from_millis(700);
+
+// Jumps tried on each frame while looking for a solution, so that the
+// window keeps responding during the search
+const SOLVER_BUDGET: usize = 20_000;
+
+// Longest position code that can be typed in
+const MAX_CODE_LENGTH: usize = 64;
+
+// Time limits of the challenge mode, the first one being the usual game
+const TIME_LIMITS: [Option<u64>; 4] = [None, Some(180), Some(300), Some(600)];
+
+// Speeds of the animations offered in the settings, with the duration of
+// the animation of a move in milliseconds
+const ANIMATION_SPEEDS: [(&str, u64); 4] =
+    [("off", 0), ("fast", 150), ("normal", 300), ("slow", 600)];
+
+pub struct Fonts {
+    pub title: FontRenderer,
+    pub menu: FontRenderer,
+    pub other: FontRenderer,
+}
+
+// What is shown in the window. Each screen draws itself and handles its own
+// input; `Playing`, `Paused`, `Won` and `Lost` have a game going on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Screen {
+    MainMenu,
+    BoardSelect,
+    Playing,
+    Paused,
+    Won,
+    Lost,
+    Settings,
+    Stats,
+    // Moves of a finished game, or of a solution, played by themselves
+    Replay,
+    // Typing in the code of a position to play from it
+    EnterCode,
+}
+
+#[derive(Clone, Copy)]
+enum MainMenuEntry {
+    Continue,
+    Play,
+    LoadPosition,
+    Challenge,
+    Statistics,
+    Settings,
+}
+
+#[derive(Clone, Copy)]
+enum BoardSelectEntry {
+    // Index in the registry of boards
+    Board(usize),
+    Back,
+}
+
+#[derive(Clone, Copy)]
+enum SettingsEntry {
+    Theme(usize),
+    Hints,
+    Animations,
+    Back,
+}
+
+#[derive(Clone, Copy)]
+enum GameEntry {
+    Resume,
+    Restart,
+    Undo,
+    Replay,
+    Solution,
+    Quit,
+}
+
+// Moves played by themselves on a board: the replay of a finished game, or
+// a solution from its start once the solver has found it
+struct Demo {
+    title: &'static str,
+    sb: ScreenBoard,
+    moves: Vec<Move>,
+    next: usize,
+    next_at: Instant,
+    solver: Option<Solver>,
+    // Why there are no moves to show, when the solver didn't find any
+    failure: Option<&'static str>,
+}
+
+// Size of a board and best result of the player on it, for the menu
+fn board_details(board: &Board, board_stats: &stats::BoardStats) -> String {
+    let best = match (board_stats.fewest_pegs, board_stats.fewest_moves) {
+        (Some(1), Some(moves)) => format!("won in {} moves", moves),
+        (Some(pegs), _) => format!("best: {} pegs left", pegs),
+        (None, _) => "not played yet".to_string(),
+    };
+    format!(
+        "{} pegs, {}x{} - {}",
+        board.count_peg(),
+        board.width(),
+        board.height(),
+        best
+    )
+}
+
+pub struct App {
+    pub screen: Screen,
+    themes: Vec<Theme>,
+    theme_index: usize,
+    registry: Vec<BoardEntry>,
+    // Theme and hints, remembered from one session to the next
+    preferences: Preferences,
+    stats: Stats,
+    // Game that can be continued from the main menu, as read from the save
+    // file. While a game is going on the save file is its own, so this is
+    // only read again when leaving it.
+    saved_game: Option<SavedGame>,
+    time_limit: Option<u64>,
+
+    // The game going on, if any
+    game: Option<ScreenBoard>,
+    selection: Selection,
+    animation: Option<Animation>,
+    // Where the left button was pressed on a peg, and whether it moved since
+    drag_start: Option<Vector>,
+    dragging: bool,
+    dropped_at: Option<Vector>,
+    // Whether to show the keyboard cursor on the board
+    keyboard_cursor: bool,
+    // Message shown under the controls, until the next move
+    notice: Option<String>,
+    new_best: bool,
+    demo: Option<Demo>,
+
+    // Last position code copied, and the one being typed in
+    copied_code: Option<String>,
+    code_input: String,
+    code_error: bool,
+
+    // Entry of each menu chosen by Enter
+    main_menu: ListState,
+    board_select: ListState,
+    settings: ListState,
+    game_menu: ListState,
+    controls: Controls,
+}
+
+impl App {
+    pub fn new(themes: Vec<Theme>, registry: Vec<BoardEntry>) -> Self {
+        let preferences = Preferences::load();
+        let theme_index = themes
+            .iter()
+            .position(|theme| theme.name == preferences.theme)
+            .unwrap_or(0);
+        App {
+            screen: Screen::MainMenu,
+            themes,
+            theme_index,
+            registry,
+            preferences,
+            stats: Stats::load(),
+            saved_game: save::load_game(),
+            time_limit: TIME_LIMITS[0],
+            game: None,
+            selection: Selection::default(),
+            animation: None,
+            drag_start: None,
+            dragging: false,
+            dropped_at: None,
+            keyboard_cursor: false,
+            notice: None,
+            new_best: false,
+            demo: None,
+            copied_code: None,
+            code_input: String::new(),
+            code_error: false,
+            main_menu: ListState::default(),
+            board_select: ListState::default(),
+            settings: ListState::default(),
+            game_menu: ListState::default(),
+            controls: Controls::default(),
+        }
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.themes[self.theme_index]
+    }
+
+    // Start a game, forgetting anything left from the previous one
+    fn start(&mut self, sb: ScreenBoard) {
+        self.game = Some(sb);
+        self.saved_game = None;
+        self.selection.clear();
+        self.animation = None;
+        self.drag_start = None;
+        self.dragging = false;
+        self.dropped_at = None;
+        self.notice = None;
+        self.new_best = false;
+        self.screen = Screen::Playing;
+    }
+
+    // Start a game that hasn't been played yet, which counts in the
+    // statistics from now on
+    fn start_new(&mut self, sb: ScreenBoard) {
+        game::count_game(&sb, &mut self.stats, &self.registry);
+        self.start(sb);
+    }
+
+    fn restart(&mut self) {
+        if let Some(sb) = self.game.take() {
+            // Restarting abandons the game, so there is nothing left to continue
+            save::clear_saved_game();
+            self.start_new(sb.restarted());
+        }
+    }
+
+    // Leave the game for the main menu, keeping it to be continued later
+    fn quit_game(&mut self) {
+        if let Some(mut sb) = self.game.take() {
+            // Saving leaves the save file as it is for a finished game,
+            // which was cleared when it ended
+            sb.save();
+            self.saved_game = save::load_game();
+        }
+        self.screen = Screen::MainMenu;
+    }
+
+    // Start a game from the position written in `code`, on one of the
+    // boards of the registry; false if the code can't be read
+    pub fn open_position(&mut self, code: &str) -> bool {
+        let layouts: Vec<Board> = self.registry.iter().map(|e| e.board.clone()).collect();
+        match code::decode(code, &layouts) {
+            Ok(board) => {
+                let mut sb = ScreenBoard::new(board);
+                sb.time_limit = self.time_limit.map(Duration::from_secs);
+                self.start_new(sb);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    fn pause(&mut self) {
+        if let Some(sb) = self.game.as_mut() {
+            sb.pause();
+            sb.save();
+        }
+        self.selection.clear();
+        self.drag_start = None;
+        self.dragging = false;
+        self.game_menu = ListState::default();
+        self.screen = Screen::Paused;
+    }
+
+    fn unpause(&mut self) {
+        if let Some(sb) = self.game.as_mut() {
+            sb.unpause();
+        }
+        self.screen = Screen::Playing;
+    }
+
+    fn main_menu_list(&self) -> List<MainMenuEntry> {
+        let mut items = Vec::new();
+        if self.saved_game.is_some() {
+            items.push(Item::Button(
+                "Continue".to_string(),
+                MainMenuEntry::Continue,
+            ));
+        }
+        items.push(Item::Button("Play".to_string(), MainMenuEntry::Play));
+        items.push(Item::Button(
+            "Load a position".to_string(),
+            MainMenuEntry::LoadPosition,
+        ));
+        items.push(Item::Button(
+            match self.time_limit {
+                Some(limit) => {
+                    format!("Challenge: {}", format_duration(Duration::from_secs(limit)))
+                }
+                None => "Challenge: off".to_string(),
+            },
+            MainMenuEntry::Challenge,
+        ));
+        items.push(Item::Button(
+            "Statistics".to_string(),
+            MainMenuEntry::Statistics,
+        ));
+        items.push(Item::Button(
+            "Settings".to_string(),
+            MainMenuEntry::Settings,
+        ));
+        List::new(items, (210., 230., 180., 330.), 50.)
+    }
+
+    fn board_select_list(&self) -> List<BoardSelectEntry> {
+        let mut items: Vec<Item<BoardSelectEntry>> = self
+            .registry
+            .iter()
+            .enumerate()
+            .map(|(n, entry)| Item::BoardButton {
+                label: entry.title.clone(),
+                details: board_details(&entry.board, &self.stats.get(&entry.key)),
+                board: entry.board.clone(),
+                action: BoardSelectEntry::Board(n),
+            })
+            .collect();
+        items.push(Item::Button("Back".to_string(), BoardSelectEntry::Back));
+        List::new(items, (120., 230., 360., 330.), 60.)
+    }
+
+    fn settings_list(&self) -> List<SettingsEntry> {
+        let mut items = vec![Item::Label("Theme:".to_string())];
+        for (n, theme) in self.themes.iter().enumerate() {
+            let mark = if n == self.theme_index { "> " } else { "" };
+            items.push(Item::Button(
+                format!("{}{}", mark, theme.name),
+                SettingsEntry::Theme(n),
+            ));
+        }
+        items.push(Item::Button(
+            format!(
+                "Hints: {}",
+                if self.preferences.show_aids {
+                    "on"
+                } else {
+                    "off"
+                }
+            ),
+            SettingsEntry::Hints,
+        ));
+        let duration = self.preferences.animation_duration;
+        items.push(Item::Button(
+            match ANIMATION_SPEEDS
+                .iter()
+                .find(|&&(_, ms)| Duration::from_millis(ms) == duration)
+            {
+                Some((name, _)) => format!("Animations: {}", name),
+                None => format!("Animations: {} ms", duration.as_millis()),
+            },
+            SettingsEntry::Animations,
+        ));
+        items.push(Item::Button("Back".to_string(), SettingsEntry::Back));
+        List::new(items, (200., 230., 200., 330.), 50.)
+    }
+
+    // Menu shown over the board when the game is paused or over
+    fn game_menu_list(&self) -> List<GameEntry> {
+        let items = match self.screen {
+            Screen::Paused => vec![
+                Item::Button("Resume".to_string(), GameEntry::Resume),
+                Item::Button("Restart".to_string(), GameEntry::Restart),
+                Item::Button("Quit to menu".to_string(), GameEntry::Quit),
+            ],
+            Screen::Won => vec![
+                Item::Button("Play again".to_string(), GameEntry::Restart),
+                Item::Button("Replay".to_string(), GameEntry::Replay),
+                Item::Button("Quit to menu".to_string(), GameEntry::Quit),
+            ],
+            _ => {
+                let mut items = vec![Item::Button("Retry".to_string(), GameEntry::Restart)];
+                // Moves can be taken back, unless the clock ran out
+                if self
+                    .game
+                    .as_ref()
+                    .is_some_and(|sb| !sb.is_time_up() && !sb.record.moves.is_empty())
+                {
+                    items.push(Item::Button("Undo last move".to_string(), GameEntry::Undo));
+                }
+                items.push(Item::Button("Replay".to_string(), GameEntry::Replay));
+                items.push(Item::Button(
+                    "See a solution".to_string(),
+                    GameEntry::Solution,
+                ));
+                items.push(Item::Button("Quit to menu".to_string(), GameEntry::Quit));
+                items
+            }
+        };
+        if self.screen == Screen::Paused {
+            List::new(items, (210., 260., 180., 200.), 50.)
+        } else {
+            List::new(items, (210., 285., 180., 240.), 45.)
+        }
+    }
+
+    // Play the moves of the finished game from its start, or a solution
+    // from there, which has to be searched for first
+    fn start_demo(&mut self, solution: bool) {
+        let start = match self.game.as_ref() {
+            Some(sb) => sb.record.start.clone(),
+            None => return,
+        };
+        // Boards that can't be solved are told apart before the search, and
+        // the search itself gives up after a while on the larger boards
+        let (title, moves, solver) = if solution {
+            ("A solution", Vec::new(), Solver::new(&start).ok())
+        } else {
+            let moves = self.game.as_ref().map(|sb| sb.record.moves.clone());
+            ("Replay of your game", moves.unwrap_or_default(), None)
+        };
+        self.demo = Some(Demo {
+            title,
+            failure: if solution && solver.is_none() {
+                Some("This board is too large to look for a solution.")
+            } else {
+                None
+            },
+            sb: ScreenBoard::new(start),
+            moves,
+            next: 0,
+            next_at: Instant::now() + DEMO_STEP,
+            solver,
+        });
+        self.animation = None;
+        self.screen = Screen::Replay;
+    }
+
+    // Leave a replay for the summary of the game
+    fn end_demo(&mut self) {
+        self.demo = None;
+        self.animation = None;
+        self.game_menu = ListState::default();
+        self.screen = match self.game.as_ref() {
+            Some(sb) if sb.board.count_peg() == 1 => Screen::Won,
+            _ => Screen::Lost,
+        };
+    }
+
+    // Handle one event of the window, keyboard, mouse or gamepad. `position`
+    // is where the mouse is, in the coordinates of the camera.
+    pub fn event(
+        &mut self,
+        event: &Event,
+        position: Vector,
+        ctrl: bool,
+        layout: &Layout,
+        window: &Window,
+    ) {
+        let command = self.controls.command(event, ctrl);
+        match self.screen {
+            Screen::MainMenu => {
+                let list = self.main_menu_list();
+                list.update(&mut self.main_menu);
+                if let Event::PointerMoved(_) = event {
+                    window.set_cursor_icon(
+                        if list.button_at(&self.main_menu, layout, position).is_some() {
+                            Some(CursorIcon::Grabbing)
+                        } else {
+                            Some(CursorIcon::Default)
+                        },
+                    );
+                }
+                match list.handle(&mut self.main_menu, layout, event, position, command) {
+                    Some(MainMenuEntry::Continue) => {
+                        if let Some(sb) = self.saved_game.take().and_then(ScreenBoard::resume) {
+                            window.set_cursor_icon(Some(CursorIcon::Default));
+                            self.start(sb);
+                        }
+                    }
+                    Some(MainMenuEntry::Play) => self.screen = Screen::BoardSelect,
+                    Some(MainMenuEntry::LoadPosition) => {
+                        self.code_input.clear();
+                        self.code_error = false;
+                        self.screen = Screen::EnterCode;
+                    }
+                    // Cycle through the time limits of the challenge mode
+                    Some(MainMenuEntry::Challenge) => {
+                        let index = TIME_LIMITS.iter().position(|&l| l == self.time_limit);
+                        self.time_limit = TIME_LIMITS[(index.unwrap_or(0) + 1) % TIME_LIMITS.len()];
+                    }
+                    Some(MainMenuEntry::Statistics) => self.screen = Screen::Stats,
+                    Some(MainMenuEntry::Settings) => {
+                        // Start on the current theme, below the "Theme:" label
+                        self.settings.focus = self.theme_index + 1;
+                        self.screen = Screen::Settings;
+                    }
+                    None => {}
+                }
+            }
+            Screen::BoardSelect => {
+                let list = self.board_select_list();
+                list.update(&mut self.board_select);
+                if let Event::PointerMoved(_) = event {
+                    window.set_cursor_icon(
+                        if list
+                            .button_at(&self.board_select, layout, position)
+                            .is_some()
+                        {
+                            Some(CursorIcon::Grabbing)
+                        } else {
+                            Some(CursorIcon::Default)
+                        },
+                    );
+                }
+                if command == Some(Command::Cancel) {
+                    self.screen = Screen::MainMenu;
+                }
+                match list.handle(&mut self.board_select, layout, event, position, command) {
+                    Some(BoardSelectEntry::Board(n)) => {
+                        let mut sb = ScreenBoard::new(self.registry[n].board.clone());
+                        sb.time_limit = self.time_limit.map(Duration::from_secs);
+                        window.set_cursor_icon(Some(CursorIcon::Default));
+                        self.start_new(sb);
+                    }
+                    Some(BoardSelectEntry::Back) => self.screen = Screen::MainMenu,
+                    None => {}
+                }
+            }
+            Screen::Settings => {
+                let list = self.settings_list();
+                list.update(&mut self.settings);
+                let chosen = if command == Some(Command::Cancel) {
+                    Some(SettingsEntry::Back)
+                } else {
+                    list.handle(&mut self.settings, layout, event, position, command)
+                };
+                match chosen {
+                    Some(SettingsEntry::Theme(n)) => {
+                        self.theme_index = n;
+                        self.preferences.theme = self.themes[n].name.clone();
+                        self.preferences.save();
+                    }
+                    Some(SettingsEntry::Hints) => {
+                        self.preferences.show_aids = !self.preferences.show_aids;
+                        self.preferences.save();
+                    }
+                    Some(SettingsEntry::Animations) => {
+                        let duration = self.preferences.animation_duration;
+                        let index = ANIMATION_SPEEDS
+                            .iter()
+                            .position(|&(_, ms)| Duration::from_millis(ms) == duration);
+                        let (_, ms) = ANIMATION_SPEEDS
+                            [index.map_or(0, |index| index + 1) % ANIMATION_SPEEDS.len()];
+                        self.preferences.animation_duration = Duration::from_millis(ms);
+                        self.preferences.save();
+                    }
+                    Some(SettingsEntry::Back) => self.screen = Screen::MainMenu,
+                    None => {}
+                }
+            }
+            // Any key, click or button goes back to the menu
+            Screen::Stats => match event {
+                Event::KeyboardInput(k_ev) if k_ev.is_down() => self.screen = Screen::MainMenu,
+                Event::PointerInput(p_ev) if p_ev.is_down() => self.screen = Screen::MainMenu,
+                Event::GamepadButton(b_ev) if b_ev.is_down() => self.screen = Screen::MainMenu,
+                _ => {}
+            },
+            Screen::Playing => self.playing_event(event, position, command),
+            Screen::Paused | Screen::Won | Screen::Lost => {
+                let list = self.game_menu_list();
+                list.update(&mut self.game_menu);
+                let chosen = match command {
+                    Some(Command::Restart) => Some(GameEntry::Restart),
+                    Some(Command::Cancel) | Some(Command::Pause)
+                        if self.screen == Screen::Paused =>
+                    {
+                        Some(GameEntry::Resume)
+                    }
+                    _ => list.handle(&mut self.game_menu, layout, event, position, command),
+                };
+                match chosen {
+                    Some(GameEntry::Resume) => self.unpause(),
+                    Some(GameEntry::Restart) => self.restart(),
+                    // Going on with the game doesn't count as another one
+                    Some(GameEntry::Undo) => {
+                        if let Some(sb) = self.game.as_mut() {
+                            sb.reopen();
+                            sb.undo();
+                        }
+                        self.new_best = false;
+                        self.screen = Screen::Playing;
+                    }
+                    Some(GameEntry::Replay) => self.start_demo(false),
+                    Some(GameEntry::Solution) => self.start_demo(true),
+                    Some(GameEntry::Quit) => self.quit_game(),
+                    None => {}
+                }
+            }
+            // Keys are read as they are, as the letters of the code would
+            // otherwise move a cursor
+            Screen::EnterCode => match event {
+                Event::ReceivedCharacter(c_ev) => {
+                    let c = c_ev.character();
+                    if !ctrl
+                        && (c.is_ascii_alphanumeric() || c == '.')
+                        && self.code_input.len() < MAX_CODE_LENGTH
+                    {
+                        self.code_input.push(c);
+                        self.code_error = false;
+                    }
+                }
+                Event::KeyboardInput(k_ev) if k_ev.is_down() => match k_ev.key() {
+                    Key::Back => {
+                        self.code_input.pop();
+                        self.code_error = false;
+                    }
+                    // The clipboard of the system, or else the last code copied
+                    // in the game
+                    Key::V if ctrl => {
+                        if let Some(pasted) = sharing::paste()
+                            .filter(|text| !text.is_empty())
+                            .or_else(|| self.copied_code.clone())
+                        {
+                            self.code_input = pasted
+                                .chars()
+                                .filter(|&c| c.is_ascii_alphanumeric() || c == '.')
+                                .take(MAX_CODE_LENGTH)
+                                .collect();
+                            self.code_error = false;
+                        }
+                    }
+                    Key::Return => {
+                        let input = self.code_input.clone();
+                        self.code_error = !self.open_position(&input);
+                    }
+                    Key::Escape => self.screen = Screen::MainMenu,
+                    _ => {}
+                },
+                _ if command == Some(Command::Cancel) => self.screen = Screen::MainMenu,
+                _ => {}
+            },
+            // Any key, click or button goes back to the summary of the game
+            Screen::Replay => match event {
+                Event::KeyboardInput(k_ev) if k_ev.is_down() => self.end_demo(),
+                Event::PointerInput(p_ev) if p_ev.is_down() => self.end_demo(),
+                Event::GamepadButton(b_ev) if b_ev.is_down() => self.end_demo(),
+                _ => {}
+            },
+        }
+    }
+
+    fn playing_event(&mut self, event: &Event, position: Vector, command: Option<Command>) {
+        let sb = match self.game.as_mut() {
+            Some(sb) => sb,
+            None => return,
+        };
+
+        // Keyboard and gamepad
+        match command {
+            Some(Command::Restart) => return self.restart(),
+            Some(Command::Pause) => return self.pause(),
+            // Esc drops the selected peg, or pauses when there is none
+            Some(Command::Cancel) if self.selection.src.is_none() => return self.pause(),
+            Some(Command::Cancel) => self.selection.clear(),
+            Some(Command::Save) => {
+                sb.save();
+                self.notice = Some("Game saved.".to_string());
+            }
+            Some(Command::Copy) => {
+                let code = code::encode(&sb.board);
+                sharing::copy(&code);
+                self.notice = Some(format!("Position code copied: {}", code));
+                self.copied_code = Some(code);
+            }
+            Some(Command::ToggleAids) => {
+                self.preferences.show_aids = !self.preferences.show_aids;
+                self.preferences.save();
+            }
+            Some(Command::Undo) if self.animation.is_none() => {
+                self.selection.clear();
+                sb.undo();
+            }
+            // Pick up the peg under the cursor or drop the selected one there
+            Some(Command::Select) => {
+                self.keyboard_cursor = true;
+                if !sb.finished && self.animation.is_none() {
+                    self.selection.pick(&sb.board, Some(sb.cursor));
+                }
+            }
+            Some(command) => {
+                self.keyboard_cursor = true;
+                if let Some(step) = command.direction() {
+                    sb.cursor = selection::move_cursor(&sb.board, sb.cursor, step);
+                }
+            }
+            None => {}
+        }
+
+        // Mouse and window
+        match event {
+            // Pause when the window loses the focus, as it may be about to be closed
+            Event::FocusChanged(f_ev) if !f_ev.is_focused() => self.pause(),
+            Event::PointerMoved(_) => {
+                if let Some(start) = self.drag_start {
+                    if position.distance(start) > DRAG_THRESHOLD {
+                        self.dragging = true;
+                    }
+                }
+            }
+            Event::PointerInput(p_ev) => {
+                self.keyboard_cursor = false;
+                // Left click : select a peg or select the destination of the previously selected_src peg
+                if p_ev.button() == MouseButton::Left && p_ev.is_down() {
+                    // Input is held back while a move is animated
+                    if !sb.finished && self.animation.is_none() {
+                        let cell = sb.get_row_col_cell_clicked(position);
+                        self.selection.pick(&sb.board, cell);
+                        // Pressing on a peg may also start dragging it
+                        if cell.is_some() && self.selection.src == cell {
+                            self.drag_start = Some(position);
+                        }
+                    }
+
+                // Left button released after a drag : drop the peg
+                } else if p_ev.button() == MouseButton::Left {
+                    if let (true, Some(src)) = (self.dragging, self.selection.src) {
+                        match sb.get_row_col_cell_clicked(position) {
+                            Some(dest) if sb.board.is_valid_move(src, dest) => {
+                                self.selection.dest = Some(dest);
+                                self.dropped_at = Some(position);
+                            }
+                            _ => {
+                                self.animation = Some(Animation::DropBack(DropBack::new(
+                                    src,
+                                    position,
+                                    self.preferences.animation_duration,
+                                )));
+                            }
+                        }
+                    }
+                    self.drag_start = None;
+                    self.dragging = false;
+
+                // Right click : deselect the current selected_src peg if any
+                } else if p_ev.button() == MouseButton::Right && p_ev.is_down() {
+                    self.selection.src = None;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // What happens on every frame, once the events are handled: moves,
+    // animations, the clock and the end of the game
+    pub fn update(&mut self) {
+        match self.screen {
+            Screen::Playing => self.update_game(),
+            Screen::Replay => self.update_demo(),
+            _ => {}
+        }
+    }
+
+    fn update_demo(&mut self) {
+        let demo = match self.demo.as_mut() {
+            Some(demo) => demo,
+            None => return,
+        };
+        if let Some(solver) = demo.solver.as_mut() {
+            if let Some(outcome) = solver.step(SOLVER_BUDGET) {
+                match outcome {
+                    Outcome::Solved(moves) => demo.moves = moves,
+                    Outcome::Unsolvable => {
+                        demo.failure = Some("There is no solution from the start of this board.")
+                    }
+                    Outcome::GaveUp => demo.failure = Some("No solution found."),
+                }
+                demo.solver = None;
+                demo.next_at = Instant::now() + DEMO_STEP;
+            }
+        } else if demo.next < demo.moves.len() && Instant::now() >= demo.next_at {
+            let (src, dest) = demo.moves[demo.next];
+            demo.sb.play(src, dest);
+            self.animation = Some(Animation::Jump(Jump::new(
+                (src, dest),
+                self.preferences.animation_duration,
+            )));
+            demo.next += 1;
+            demo.next_at = Instant::now() + DEMO_STEP;
+        }
+        if self.animation.as_ref().is_some_and(|a| a.is_done()) {
+            self.animation = None;
+        }
+    }
+
+    fn update_game(&mut self) {
+        let sb = match self.game.as_mut() {
+            Some(sb) => sb,
+            None => return,
+        };
+
+        if !sb.finished && sb.is_time_up() {
+            self.selection.clear();
+            self.new_best = game::end_game(sb, &mut self.stats, &self.registry);
+        }
+
+        // Do the move requested by the user
+        if let Some((src, dest)) = self.selection.take_move() {
+            sb.play(src, dest);
+            self.animation = Some(Animation::Jump(match self.dropped_at.take() {
+                Some(at) => Jump::dropped((src, dest), at, self.preferences.animation_duration),
+                None => Jump::new((src, dest), self.preferences.animation_duration),
+            }));
+            self.notice = None;
+            if sb.board.has_valid_move_left() {
+                sb.save();
+            } else {
+                self.new_best = game::end_game(sb, &mut self.stats, &self.registry);
+            }
+        }
+
+        if self.animation.as_ref().is_some_and(|a| a.is_done()) {
+            self.animation = None;
+        }
+
+        // Keep showing the board until the last jump has been animated
+        if sb.finished && self.animation.is_none() {
+            self.drag_start = None;
+            self.dragging = false;
+            self.game_menu = ListState::default();
+            self.screen = if sb.board.count_peg() == 1 {
+                Screen::Won
+            } else {
+                Screen::Lost
+            };
+        }
+    }
+
+    pub fn draw(
+        &mut self,
+        gfx: &mut Graphics,
+        fonts: &mut Fonts,
+        layout: &Layout,
+        mouse: Vector,
+    ) -> quicksilver::Result<()> {
+        if let Some(sb) = self.game.as_mut() {
+            sb.fit(layout.board_area(), layout.max_cell_size());
+        }
+        if let Some(demo) = self.demo.as_mut() {
+            demo.sb.fit(layout.board_area(), layout.max_cell_size());
+        }
+        let theme = &self.themes[self.theme_index];
+        gfx.clear(theme.background);
+        fonts
+            .title
+            .draw(gfx, "Peg Solitaire", theme.text, layout.top_left(20., 50.))?;
+
+        match self.screen {
+            Screen::MainMenu => {
+                let list = self.main_menu_list();
+                list.update(&mut self.main_menu);
+                list.draw(&self.main_menu, gfx, &mut fonts.other, layout, theme)?;
+                fonts.other.draw(
+                    gfx,
+                    "[Up/Down] choose  [Enter] confirm",
+                    theme.text,
+                    layout.point(170., 590.),
+                )?;
+            }
+            Screen::BoardSelect => {
+                let list = self.board_select_list();
+                list.update(&mut self.board_select);
+                fonts.menu.draw(
+                    gfx,
+                    "Board selection:",
+                    theme.text,
+                    layout.point(190.0, 200.0),
+                )?;
+                list.draw(&self.board_select, gfx, &mut fonts.other, layout, theme)?;
+                fonts.other.draw(
+                    gfx,
+                    "[Up/Down] choose  [Enter] confirm  [Esc] back",
+                    theme.text,
+                    layout.point(130., 590.),
+                )?;
+            }
+            Screen::Settings => {
+                let list = self.settings_list();
+                list.update(&mut self.settings);
+                fonts
+                    .menu
+                    .draw(gfx, "Settings", theme.text, layout.point(240.0, 200.0))?;
+                list.draw(&self.settings, gfx, &mut fonts.other, layout, theme)?;
+                fonts.other.draw(
+                    gfx,
+                    "[Up/Down] choose  [Enter] confirm  [Esc] back",
+                    theme.text,
+                    layout.point(130., 590.),
+                )?;
+            }
+            Screen::Stats => self.draw_stats(gfx, fonts, layout)?,
+            Screen::EnterCode => {
+                fonts.menu.draw(
+                    gfx,
+                    "Load a position",
+                    theme.text,
+                    layout.point(200.0, 200.0),
+                )?;
+                fonts.other.draw(
+                    gfx,
+                    "Type in the code of a position, such as english.777X774:",
+                    theme.text,
+                    layout.point(90., 250.),
+                )?;
+                let field = Rectangle::new(
+                    layout.point(90., 270.),
+                    Vector::new(420., 40.) * layout.scale(),
+                );
+                gfx.fill_rect(&field, theme.button);
+                gfx.stroke_rect(&field, theme.focus);
+                fonts.other.draw(
+                    gfx,
+                    &format!("{}_", self.code_input),
+                    theme.label,
+                    layout.point(100., 295.),
+                )?;
+                if self.code_error {
+                    fonts.other.draw(
+                        gfx,
+                        "This code matches no position of the known boards.",
+                        theme.alert,
+                        layout.point(90., 340.),
+                    )?;
+                }
+                fonts.other.draw(
+                    gfx,
+                    "[Enter] load  [Ctrl+V] paste  [Esc] back",
+                    theme.text,
+                    layout.point(90., 590.),
+                )?;
+            }
+            Screen::Playing => {
+                if let Some(sb) = self.game.as_ref() {
+                    self.draw_game(sb, gfx, fonts, layout, mouse)?;
+                }
+            }
+            Screen::Paused | Screen::Won | Screen::Lost => {
+                if let Some(sb) = self.game.as_ref() {
+                    self.draw_game(sb, gfx, fonts, layout, mouse)?;
+                }
+                self.draw_game_menu(gfx, fonts, layout)?;
+            }
+            Screen::Replay => {
+                if let Some(demo) = self.demo.as_ref() {
+                    self.draw_game(&demo.sb, gfx, fonts, layout, mouse)?;
+                    let status = if demo.solver.is_some() {
+                        "Looking for a solution...".to_string()
+                    } else if let Some(failure) = demo.failure {
+                        failure.to_string()
+                    } else {
+                        format!("Move {} of {}", demo.next, demo.moves.len())
+                    };
+                    fonts
+                        .menu
+                        .draw(gfx, demo.title, theme.text, layout.top_left(20., 85.))?;
+                    fonts
+                        .other
+                        .draw(gfx, &status, theme.text, layout.bottom(150., 565.))?;
+                    fonts.other.draw(
+                        gfx,
+                        "Click or press any key to go back.",
+                        theme.text,
+                        layout.bottom(150., 585.),
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Display the statistics of each board
+    fn draw_stats(
+        &self,
+        gfx: &mut Graphics,
+        fonts: &mut Fonts,
+        layout: &Layout,
+    ) -> quicksilver::Result<()> {
+        let theme = self.theme();
+        fonts
+            .menu
+            .draw(gfx, "Statistics", theme.text, layout.point(230.0, 150.0))?;
+        let columns = [
+            (30., "Board"),
+            (160., "Played"),
+            (240., "Wins"),
+            (310., "Fewest pegs"),
+            (420., "Moves"),
+            (500., "Time"),
+        ];
+        for (x, header) in &columns {
+            fonts
+                .other
+                .draw(gfx, header, theme.text, layout.point(*x, 210.))?;
+        }
+        for (n, entry) in self.registry.iter().enumerate() {
+            let board_stats = self.stats.get(&entry.key);
+            let or_dash = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+            let values = [
+                entry.key.clone(),
+                board_stats.played.to_string(),
+                board_stats.wins.to_string(),
+                or_dash(board_stats.fewest_pegs.map(|pegs| pegs.to_string())),
+                or_dash(board_stats.fewest_moves.map(|moves| moves.to_string())),
+                or_dash(board_stats.fastest.map(format_duration)),
+            ];
+            for ((x, _), value) in columns.iter().zip(values.iter()) {
+                fonts.other.draw(
+                    gfx,
+                    value,
+                    theme.text,
+                    layout.point(*x, 250. + n as f32 * 27.),
+                )?;
+            }
+        }
+        fonts.other.draw(
+            gfx,
+            "Click or press any key to go back.",
+            theme.text,
+            layout.point(180., 500.),
+        )?;
+        Ok(())
+    }
+
+    // The board with the clock and the hints, as played or behind the menu
+    // of a paused or finished game
+    fn draw_game(
+        &self,
+        sb: &ScreenBoard,
+        gfx: &mut Graphics,
+        fonts: &mut Fonts,
+        layout: &Layout,
+        mouse: Vector,
+    ) -> quicksilver::Result<()> {
+        let theme = self.theme();
+        let selection = &self.selection;
+        let animation = &self.animation;
+        let show_aids = self.preferences.show_aids;
+
+        let (clock, clock_color) = match sb.remaining() {
+            Some(remaining) if remaining < Duration::from_secs(30) => {
+                (format_duration(remaining), theme.alert)
+            }
+            Some(remaining) => (format_duration(remaining), theme.text),
+            None => (format_duration(sb.elapsed()), theme.text),
+        };
+        if self.screen != Screen::Replay {
+            fonts
+                .menu
+                .draw(gfx, &clock, clock_color, layout.top_right(500., 50.))?;
+        }
+
+        if self.screen == Screen::Playing {
+            fonts.other.draw(
+                gfx,
+                "[U] undo  [R] restart  [Ctrl+S] save  [Ctrl+C] copy code  [H] hints",
+                theme.text,
+                layout.bottom(150., 585.),
+            )?;
+            if let Some(notice) = &self.notice {
+                fonts
+                    .other
+                    .draw(gfx, notice, theme.text, layout.bottom_left(20., 565.))?;
+            }
+            fonts.other.draw(
+                gfx,
+                "[Arrows/WASD] move  [Space/Enter] pick up or drop  [Esc] cancel or pause",
+                theme.text,
+                layout.top_left(20., 85.),
+            )?;
+        }
+        fonts.other.draw(
+            gfx,
+            &format!("{} pieces left", sb.board.count_peg()),
+            theme.text,
+            Vector::new(
+                sb.board_margin_left + sb.board_size.0 - layout.len(100.),
+                sb.board_margin_top + sb.board_size.1 + layout.len(20.),
+            ),
+        )?;
+
+        // Draw the current state of the board
+        let destinations = match selection.src {
+            Some(src) if show_aids && animation.is_none() => sb.board.destinations_from(src),
+            _ => Vec::new(),
+        };
+        for i in 0..sb.board.width() {
+            for j in 0..sb.board.height() {
+                let cell = sb.board.get_cell(i, j);
+                match cell {
+                    // The destination of an animated jump stays empty until the peg lands,
+                    // as does the cell of a peg that is dragged or going back to it
+                    Cell::Peg
+                        if animation.as_ref().map(|a| a.hidden_cell()) == Some((i, j))
+                            || (self.dragging && selection.src == Some((i, j))) =>
+                    {
+                        make_hole(sb, i, j, theme, gfx);
+                    }
+                    Cell::Peg if show_aids && !sb.finished && !sb.board.can_move((i, j)) => {
+                        make_peg(sb, i, j, theme.cell, theme.dimmed, theme, gfx);
+                    }
+                    Cell::Peg => {
+                        make_peg(sb, i, j, theme.cell, theme.peg, theme, gfx);
+                    }
+                    Cell::Hole if destinations.contains(&(i, j)) => {
+                        make_hole(sb, i, j, theme, gfx);
+                        gfx.fill_circle(
+                            &Circle::new(sb.cell_center(i, j), sb.hole_size / 2.),
+                            theme.destination,
+                        );
+                    }
+                    Cell::Hole => {
+                        make_hole(sb, i, j, theme, gfx);
+                    }
+                    _ => {}
+                };
+            }
+        }
+
+        // Frame the cell under the keyboard cursor
+        if self.keyboard_cursor && self.screen == Screen::Playing {
+            let (i, j) = sb.cursor;
+            gfx.stroke_rect(&sb.cell_rect(i, j), theme.cursor);
+        }
+
+        // Draw the jumped peg shrinking and the jumping peg on its way,
+        // or the dropped peg going back to its cell
+        match animation {
+            Some(Animation::Jump(jump)) => {
+                let ((src_i, src_j), (dest_i, dest_j)) = jump.mv;
+                let (over_i, over_j) = jump.over;
+                theme.draw_peg(
+                    sb.cell_center(over_i, over_j),
+                    sb.peg_size * jump.captured_scale(),
+                    theme.peg,
+                    gfx,
+                );
+                theme.draw_peg(
+                    jump.peg_position(sb.cell_center(src_i, src_j), sb.cell_center(dest_i, dest_j)),
+                    sb.peg_size,
+                    theme.peg,
+                    gfx,
+                );
+            }
+            Some(Animation::DropBack(drop_back)) => {
+                let (src_i, src_j) = drop_back.src;
+                theme.draw_peg(
+                    drop_back.peg_position(sb.cell_center(src_i, src_j)),
+                    sb.peg_size,
+                    theme.selection,
+                    gfx,
+                );
+            }
+            None => {}
+        }
+
+        // Toggle the color of the selected source and/or destination peg(s) if any
+        // A dragged peg follows the mouse, otherwise a red dot does
+        match selection.src {
+            Some(_) if animation.is_some() => {}
+            Some(_) if self.dragging => {
+                theme.draw_peg(mouse, sb.peg_size, theme.selection, gfx);
+            }
+            Some((i, j)) => {
+                make_peg(sb, i, j, theme.cell, theme.selection, theme, gfx);
+                gfx.fill_circle(&Circle::new(mouse, layout.len(12.0)), theme.selection);
+            }
+            None => {}
+        }
+        if let Some((i, j)) = selection.dest {
+            make_peg(sb, i, j, theme.selection, theme.cell, theme, gfx);
+        }
+        Ok(())
+    }
+
+    fn draw_game_menu(
+        &mut self,
+        gfx: &mut Graphics,
+        fonts: &mut Fonts,
+        layout: &Layout,
+    ) -> quicksilver::Result<()> {
+        let list = self.game_menu_list();
+        list.update(&mut self.game_menu);
+        let theme = &self.themes[self.theme_index];
+
+        // Veil the board so that the menu stands out
+        let veil = theme.background.with_alpha(0.8);
+        gfx.fill_rect(
+            &Rectangle::new(
+                layout.point(130., 110.),
+                Vector::new(340., 430.) * layout.scale(),
+            ),
+            veil,
+        );
+        let time_up = self.game.as_ref().is_some_and(|sb| sb.is_time_up());
+        let (title, color) = match self.screen {
+            Screen::Paused => ("Paused", theme.text),
+            Screen::Won => ("YOU WIN !!", theme.alert),
+            _ if time_up => ("Time is up !", theme.alert),
+            _ => ("No valid move left !", theme.alert),
+        };
+        if self.screen == Screen::Paused {
+            fonts
+                .menu
+                .draw(gfx, title, color, layout.point(170., 190.))?;
+        } else if let Some(sb) = self.game.as_ref() {
+            // Summary of the game
+            fonts
+                .menu
+                .draw(gfx, title, color, layout.point(150., 160.))?;
+            if self.new_best {
+                fonts.other.draw(
+                    gfx,
+                    "New personal best !",
+                    theme.alert,
+                    layout.point(150., 185.),
+                )?;
+            }
+            let pegs_left = sb.board.count_peg();
+            let lines = [
+                format!("Pegs left: {} ({})", pegs_left, rating(pegs_left)),
+                format!("Moves: {}", sb.record.move_count()),
+                format!("Time: {}", format_duration(sb.elapsed())),
+            ];
+            for (n, line) in lines.iter().enumerate() {
+                fonts.other.draw(
+                    gfx,
+                    line,
+                    theme.text,
+                    layout.point(150., 215. + n as f32 * 25.),
+                )?;
+            }
+        }
+        list.draw(&self.game_menu, gfx, &mut fonts.other, layout, theme)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+// Sharing positions through their codes (see `psolo_core::code`). On the
+// Web, codes go to the clipboard of the browser and a code can be given in
+// the address of the page, as in `index.html?position=english.777X774`.
+// Desktop builds go through the clipboard tools of the system when they
+// find one (pbcopy on macOS, clip on Windows, wl-clipboard, xclip or xsel
+// elsewhere), and also write the codes to the log.
+//
+// The Web version is built with cargo web and the `web` feature, along with
+// the `stdweb` feature of quicksilver: it is the only one to use stdweb.
+
+#[cfg(all(target_arch = "wasm32", feature = "web"))]
+use stdweb::{js, unstable::TryInto};
+
+#[cfg(not(all(target_arch = "wasm32", feature = "web")))]
+use std::io::Write;
+#[cfg(not(all(target_arch = "wasm32", feature = "web")))]
+use std::process::{Command, Stdio};
+
+// Name of the parameter of the page address holding a position code
+#[cfg(all(target_arch = "wasm32", feature = "web"))]
+const URL_PARAMETER: &str = "position";
+
+// Commands writing their input to the clipboard, and printing the clipboard,
+// tried in turn
+#[cfg(target_os = "macos")]
+const COPY_COMMANDS: &[&[&str]] = &[&["pbcopy"]];
+#[cfg(target_os = "macos")]
+const PASTE_COMMANDS: &[&[&str]] = &[&["pbpaste"]];
+#[cfg(windows)]
+const COPY_COMMANDS: &[&[&str]] = &[&["clip"]];
+#[cfg(windows)]
+const PASTE_COMMANDS: &[&[&str]] = &[&["powershell", "-NoProfile", "-Command", "Get-Clipboard"]];
+#[cfg(not(any(target_os = "macos", windows)))]
+const COPY_COMMANDS: &[&[&str]] = &[
+    &["wl-copy"],
+    &["xclip", "-selection", "clipboard"],
+    &["xsel", "--clipboard", "--input"],
+];
+#[cfg(not(any(target_os = "macos", windows)))]
+const PASTE_COMMANDS: &[&[&str]] = &[
+    &["wl-paste", "--no-newline"],
+    &["xclip", "-selection", "clipboard", "-o"],
+    &["xsel", "--clipboard", "--output"],
+];
+
+#[cfg(all(target_arch = "wasm32", feature = "web"))]
+pub fn copy(code: &str) {
+    js! {
+        if (navigator.clipboard) {
+            navigator.clipboard.writeText(@{code});
+        }
+    }
+}
+
+#[cfg(not(all(target_arch = "wasm32", feature = "web")))]
+pub fn copy(code: &str) {
+    quicksilver::log::info!("Position code: {}", code);
+    let copied = COPY_COMMANDS.iter().any(|command| {
+        let child = Command::new(command[0])
+            .args(&command[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        child.is_ok_and(|mut child| {
+            let written = child
+                .stdin
+                .take()
+                .is_some_and(|mut stdin| stdin.write_all(code.as_bytes()).is_ok());
+            child.wait().is_ok_and(|status| status.success()) && written
+        })
+    });
+    if !copied {
+        quicksilver::log::warn!("No clipboard tool found to copy the code");
+    }
+}
+
+// The text of the clipboard, if it can be read. Browsers only give it
+// asynchronously, so the Web version doesn't read it.
+#[cfg(all(target_arch = "wasm32", feature = "web"))]
+pub fn paste() -> Option<String> {
+    None
+}
+
+#[cfg(not(all(target_arch = "wasm32", feature = "web")))]
+pub fn paste() -> Option<String> {
+    PASTE_COMMANDS.iter().find_map(|command| {
+        let output = Command::new(command[0])
+            .args(&command[1..])
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        let text = String::from_utf8(output.stdout).ok()?;
+        Some(text.trim().to_string())
+    })
+}
+
+// The position code given in the address of the page, if any
+#[cfg(all(target_arch = "wasm32", feature = "web"))]
+pub fn url_position() -> Option<String> {
+    let search: String = js!(return window.location.search;).try_into().ok()?;
+    search
+        .trim_start_matches('?')
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == URL_PARAMETER)
+        .map(|(_, value)| value.to_string())
+}
+
+#[cfg(not(all(target_arch = "wasm32", feature = "web")))]
+pub fn url_position() -> Option<String> {
+    None
+}
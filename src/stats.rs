@@ -0,0 +1,188 @@
+use std::time::Duration;
+
+use quicksilver::saving::{load_raw, save_raw, Location};
+
+use crate::save::APP_NAME;
+
+const STATS: &str = "stats";
+
+// Statistics are saved with one line per board: its name, the number of
+// games played and won, then the fewest pegs left, the fewest moves and the
+// fastest time (in milliseconds), with `-` when there is no such record yet.
+
+#[derive(Debug, Default, Clone)]
+pub struct BoardStats {
+    pub played: u32,
+    pub wins: u32,
+    pub fewest_pegs: Option<usize>,
+    // Only won games count for the fewest moves and the fastest time
+    pub fewest_moves: Option<usize>,
+    pub fastest: Option<Duration>,
+}
+
+#[derive(Debug, Default)]
+pub struct Stats {
+    boards: Vec<(String, BoardStats)>,
+}
+
+// Keep the lowest of the two values, telling if `value` is a new record
+fn improve<T: PartialOrd + Copy>(best: &mut Option<T>, value: T) -> bool {
+    match best {
+        Some(b) if *b <= value => false,
+        _ => {
+            *best = Some(value);
+            true
+        }
+    }
+}
+
+fn parse_field<T: std::str::FromStr>(field: &str) -> Option<Option<T>> {
+    if field == "-" {
+        Some(None)
+    } else {
+        field.parse().ok().map(Some)
+    }
+}
+
+fn format_field<T: ToString>(field: Option<T>) -> String {
+    field.map_or_else(|| "-".to_string(), |value| value.to_string())
+}
+
+impl BoardStats {
+    fn parse(fields: &[&str]) -> Option<Self> {
+        if fields.len() != 5 {
+            return None;
+        }
+        Some(BoardStats {
+            played: fields[0].parse().ok()?,
+            wins: fields[1].parse().ok()?,
+            fewest_pegs: parse_field(fields[2])?,
+            fewest_moves: parse_field(fields[3])?,
+            fastest: parse_field(fields[4])?.map(Duration::from_millis),
+        })
+    }
+
+    fn format(&self) -> String {
+        format!(
+            "{} {} {} {} {}",
+            self.played,
+            self.wins,
+            format_field(self.fewest_pegs),
+            format_field(self.fewest_moves),
+            format_field(self.fastest.map(|time| time.as_millis())),
+        )
+    }
+}
+
+impl Stats {
+    pub fn load() -> Self {
+        let text = load_raw(Location::Data, APP_NAME, STATS)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .unwrap_or_default();
+        let boards = text
+            .lines()
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let (name, fields) = fields.split_first()?;
+                Some((name.to_string(), BoardStats::parse(fields)?))
+            })
+            .collect();
+        Stats { boards }
+    }
+
+    pub fn save(&self) {
+        let text: String = self
+            .boards
+            .iter()
+            .map(|(name, stats)| format!("{} {}\n", name, stats.format()))
+            .collect();
+        if let Err(e) = save_raw(Location::Data, APP_NAME, STATS, text.as_bytes()) {
+            quicksilver::log::warn!("Unable to save the statistics: {}", e);
+        }
+    }
+
+    pub fn get(&self, board: &str) -> BoardStats {
+        self.boards
+            .iter()
+            .find(|(name, _)| name == board)
+            .map(|(_, stats)| stats.clone())
+            .unwrap_or_default()
+    }
+
+    fn entry(&mut self, board: &str) -> &mut BoardStats {
+        let index = match self.boards.iter().position(|(name, _)| name == board) {
+            Some(index) => index,
+            None => {
+                self.boards.push((board.to_string(), BoardStats::default()));
+                self.boards.len() - 1
+            }
+        };
+        &mut self.boards[index].1
+    }
+
+    // Count a game as played as soon as it starts, so that the games left
+    // unfinished are counted too
+    pub fn start_game(&mut self, board: &str) {
+        self.entry(board).played += 1;
+    }
+
+    // Count the result of a finished game, already counted as played, and
+    // tell if it sets a new personal best
+    pub fn record_game(
+        &mut self,
+        board: &str,
+        pegs_left: usize,
+        moves: usize,
+        elapsed: Duration,
+    ) -> bool {
+        let stats = self.entry(board);
+        // Nothing to beat for the first game played to its end
+        let first_game = stats.fewest_pegs.is_none();
+        let mut new_best = improve(&mut stats.fewest_pegs, pegs_left);
+        if pegs_left == 1 {
+            stats.wins += 1;
+            new_best |= improve(&mut stats.fewest_moves, moves);
+            new_best |= improve(&mut stats.fastest, elapsed);
+        }
+        new_best && !first_game
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The first result of a board has nothing to beat, and later results
+    // are compared to it
+    #[test]
+    fn first_result_is_not_a_new_best() {
+        let mut stats = Stats::default();
+        stats.start_game("english");
+        assert!(!stats.record_game("english", 5, 27, Duration::from_secs(60)));
+        stats.start_game("english");
+        let english = stats.get("english");
+        assert_eq!((english.played, english.wins), (2, 0));
+        assert_eq!(english.fewest_pegs, Some(5));
+
+        // Games that were only started don't count as the first result
+        stats.start_game("european");
+        stats.start_game("european");
+        assert!(!stats.record_game("european", 8, 28, Duration::from_secs(60)));
+        stats.start_game("european");
+        assert!(stats.record_game("european", 4, 32, Duration::from_secs(60)));
+        assert_eq!(stats.get("european").fewest_pegs, Some(4));
+    }
+
+    #[test]
+    fn games_count_as_played_from_their_start() {
+        let mut stats = Stats::default();
+        stats.start_game("english");
+        assert_eq!(stats.get("english").played, 1);
+        stats.start_game("english");
+        assert!(!stats.record_game("english", 1, 18, Duration::from_secs(300)));
+        let english = stats.get("english");
+        assert_eq!((english.played, english.wins), (2, 1));
+        assert_eq!(english.fewest_moves, Some(18));
+    }
+}
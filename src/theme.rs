@@ -0,0 +1,205 @@
+use quicksilver::{
+    geom::{Circle, Rectangle, Vector},
+    graphics::Color,
+    Graphics,
+};
+
+// File of the `static` directory the themes are read from
+pub const THEMES_FILE: &str = "themes.txt";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PegShape {
+    Round,
+    Square,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Style {
+    Plain,
+    // Grain drawn across the cells
+    Wood,
+    // Pegs with a shine on them
+    Marble,
+}
+
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub name: String,
+    pub background: Color,
+    pub cell: Color,
+    pub hole: Color,
+    pub peg: Color,
+    // Pegs that have no valid move, when hints are shown
+    pub dimmed: Color,
+    pub selection: Color,
+    // Holes the selected peg can jump to, when hints are shown
+    pub destination: Color,
+    pub cursor: Color,
+    pub text: Color,
+    // Messages that need attention: end of the game, running out of time
+    pub alert: Color,
+    pub button: Color,
+    pub focus: Color,
+    // Text drawn on buttons
+    pub label: Color,
+    pub pegs: PegShape,
+    pub style: Style,
+}
+
+impl Default for Theme {
+    // The colours the game always had, used for whatever a theme leaves out
+    fn default() -> Self {
+        Theme {
+            name: "Classic".to_string(),
+            background: Color::from_rgba(128, 128, 128, 1.),
+            cell: Color::BLUE,
+            hole: Color::INDIGO,
+            peg: Color::YELLOW,
+            dimmed: Color {
+                r: 0.5,
+                g: 0.5,
+                b: 0.2,
+                a: 1.,
+            },
+            selection: Color::RED,
+            destination: Color::GREEN,
+            cursor: Color::WHITE,
+            text: Color::BLACK,
+            alert: Color::RED,
+            button: Color::WHITE,
+            focus: Color::YELLOW,
+            label: Color::BLACK,
+            pegs: PegShape::Round,
+            style: Style::Plain,
+        }
+    }
+}
+
+// A colour written as #RRGGBB
+fn parse_color(value: &str) -> Option<Color> {
+    let hex = value.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let component = |n: usize| u8::from_str_radix(&hex[n..n + 2], 16).ok();
+    Some(Color::from_rgba(
+        component(0)?,
+        component(2)?,
+        component(4)?,
+        1.,
+    ))
+}
+
+// Mix `color` with `other`, `amount` going from 0 (only `color`) to 1 (only `other`)
+fn mix(color: Color, other: Color, amount: f32) -> Color {
+    Color {
+        r: color.r + (other.r - color.r) * amount,
+        g: color.g + (other.g - color.g) * amount,
+        b: color.b + (other.b - color.b) * amount,
+        a: color.a,
+    }
+}
+
+impl Theme {
+    fn set(&mut self, key: &str, value: &str) -> Option<()> {
+        match key {
+            "pegs" => {
+                self.pegs = match value {
+                    "round" => PegShape::Round,
+                    "square" => PegShape::Square,
+                    _ => return None,
+                }
+            }
+            "style" => {
+                self.style = match value {
+                    "plain" => Style::Plain,
+                    "wood" => Style::Wood,
+                    "marble" => Style::Marble,
+                    _ => return None,
+                }
+            }
+            _ => {
+                let color = parse_color(value)?;
+                match key {
+                    "background" => self.background = color,
+                    "cell" => self.cell = color,
+                    "hole" => self.hole = color,
+                    "peg" => self.peg = color,
+                    "dimmed" => self.dimmed = color,
+                    "selection" => self.selection = color,
+                    "destination" => self.destination = color,
+                    "cursor" => self.cursor = color,
+                    "text" => self.text = color,
+                    "alert" => self.alert = color,
+                    "button" => self.button = color,
+                    "focus" => self.focus = color,
+                    "label" => self.label = color,
+                    _ => return None,
+                }
+            }
+        }
+        Some(())
+    }
+
+    // Read the themes of a themes file (see `static/themes.txt`), skipping
+    // the settings that can't be understood
+    pub fn parse_all(text: &str) -> Vec<Theme> {
+        let mut themes: Vec<Theme> = Vec::new();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                themes.push(Theme {
+                    name: name.trim().to_string(),
+                    ..Theme::default()
+                });
+                continue;
+            }
+            let setting = line.split_once('=');
+            let applied = match (themes.last_mut(), setting) {
+                (Some(theme), Some((key, value))) => theme.set(key.trim(), value.trim()),
+                _ => None,
+            };
+            if applied.is_none() {
+                quicksilver::log::warn!("Ignoring the theme setting '{}'", line);
+            }
+        }
+        themes
+    }
+
+    pub fn draw_cell(&self, rect: &Rectangle, color: Color, g: &mut Graphics) {
+        g.fill_rect(rect, color);
+        if self.style == Style::Wood {
+            // A few darker streaks, irregularly spaced
+            let grain = mix(color, Color::BLACK, 0.2);
+            for (y, height) in &[(0.15, 0.05), (0.4, 0.08), (0.7, 0.04), (0.85, 0.06)] {
+                g.fill_rect(
+                    &Rectangle::new(
+                        rect.pos + Vector::new(0., rect.size.y * y),
+                        Vector::new(rect.size.x, rect.size.y * height),
+                    ),
+                    grain,
+                );
+            }
+        }
+    }
+
+    pub fn draw_peg(&self, center: Vector, radius: f32, color: Color, g: &mut Graphics) {
+        match self.pegs {
+            PegShape::Round => g.fill_circle(&Circle::new(center, radius), color),
+            PegShape::Square => {
+                let half = Vector::new(radius, radius) * 0.85;
+                g.fill_rect(&Rectangle::new(center - half, half * 2.), color);
+            }
+        }
+        if self.style == Style::Marble {
+            // A light reflected on the top left of the peg
+            let shine = Vector::new(-radius, -radius) * 0.35;
+            g.fill_circle(
+                &Circle::new(center + shine, radius * 0.3),
+                mix(color, Color::WHITE, 0.6),
+            );
+        }
+    }
+}
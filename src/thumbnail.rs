@@ -0,0 +1,39 @@
+use quicksilver::{
+    geom::{Circle, Rectangle, Vector},
+    Graphics,
+};
+
+use psolo_core::board::Board;
+use psolo_core::cell::Cell;
+
+use crate::theme::Theme;
+
+// A miniature of `board`, with its pegs and holes, as large as fits in
+// `area` and centered in it
+pub fn draw(gfx: &mut Graphics, board: &Board, area: &Rectangle, theme: &Theme) {
+    let cells = board.width().max(board.height()).max(1) as f32;
+    let cell_size = area.size.x.min(area.size.y) / cells;
+    let board_size = Vector::new(board.width() as f32, board.height() as f32) * cell_size;
+    let top_left = area.pos + (area.size - board_size) / 2.;
+    // Keep some room between cells while they are large enough to tell apart
+    let margin = (cell_size * 0.1).min(2.);
+
+    for i in 0..board.width() {
+        for j in 0..board.height() {
+            let cell = board.get_cell(i, j);
+            if cell == Cell::Unusable {
+                continue;
+            }
+            let corner = top_left + Vector::new(i as f32, j as f32) * cell_size;
+            gfx.fill_rect(
+                &Rectangle::new(corner, Vector::new(cell_size - margin, cell_size - margin)),
+                theme.cell,
+            );
+            let center = corner + Vector::new(cell_size - margin, cell_size - margin) / 2.;
+            match cell {
+                Cell::Peg => theme.draw_peg(center, cell_size * 0.35, theme.peg, gfx),
+                _ => gfx.fill_circle(&Circle::new(center, cell_size * 0.2), theme.hole),
+            }
+        }
+    }
+}
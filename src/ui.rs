@@ -0,0 +1,324 @@
+use quicksilver::{
+    geom::{Rectangle, Vector},
+    graphics::FontRenderer,
+    input::{Event, ScrollDelta},
+    Graphics,
+};
+
+use psolo_core::board::Board;
+
+use crate::controls::Command;
+use crate::layout::Layout;
+use crate::theme::Theme;
+use crate::thumbnail;
+
+// Room left between two rows of a list
+const ROW_GAP: f32 = 10.;
+
+// Lines scrolled by a wheel that reports pixels rather than lines
+const PIXELS_PER_LINE: f32 = 40.;
+
+// A button, drawn as a rectangle of the window with its label
+pub fn button(
+    gfx: &mut Graphics,
+    font: &mut FontRenderer,
+    layout: &Layout,
+    (x, y, width, height): (f32, f32, f32, f32),
+    label: &str,
+    focused: bool,
+    theme: &Theme,
+) -> quicksilver::Result<()> {
+    gfx.fill_rect(
+        &Rectangle::new(Vector::new(x, y), Vector::new(width, height)),
+        if focused { theme.focus } else { theme.button },
+    );
+    font.draw(
+        gfx,
+        label,
+        theme.label,
+        Vector::new(x + layout.len(20.), y + height / 2. + layout.len(5.)),
+    )?;
+    Ok(())
+}
+
+// A button with a miniature of a board on its left, and a line of details
+// under its label
+#[allow(clippy::too_many_arguments)]
+pub fn board_button(
+    gfx: &mut Graphics,
+    font: &mut FontRenderer,
+    layout: &Layout,
+    (x, y, width, height): (f32, f32, f32, f32),
+    label: &str,
+    details: &str,
+    board: &Board,
+    focused: bool,
+    theme: &Theme,
+) -> quicksilver::Result<()> {
+    gfx.fill_rect(
+        &Rectangle::new(Vector::new(x, y), Vector::new(width, height)),
+        if focused { theme.focus } else { theme.button },
+    );
+    let padding = layout.len(4.);
+    let side = height - 2. * padding;
+    thumbnail::draw(
+        gfx,
+        board,
+        &Rectangle::new(
+            Vector::new(x + padding, y + padding),
+            Vector::new(side, side),
+        ),
+        theme,
+    );
+    let text_x = x + side + padding + layout.len(12.);
+    font.draw(
+        gfx,
+        label,
+        theme.label,
+        Vector::new(text_x, y + height / 2. - layout.len(4.)),
+    )?;
+    font.draw(
+        gfx,
+        details,
+        theme.label,
+        Vector::new(text_x, y + height / 2. + layout.len(16.)),
+    )?;
+    Ok(())
+}
+
+// A row of a list: a button that does `A` when chosen, or some text
+pub enum Item<A> {
+    Button(String, A),
+    // A button showing a board, with details about it
+    BoardButton {
+        label: String,
+        details: String,
+        board: Board,
+        action: A,
+    },
+    Label(String),
+}
+
+// What a list remembers from one frame to the next, as lists are built
+// again on every frame from what they show
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ListState {
+    pub focus: usize,
+    scroll: usize,
+}
+
+// Rows drawn one below the other in an area of the reference window, which
+// scroll when there are more of them than the area can show
+pub struct List<A> {
+    items: Vec<Item<A>>,
+    area: (f32, f32, f32, f32),
+    row_height: f32,
+}
+
+impl<A: Copy> List<A> {
+    pub fn new(items: Vec<Item<A>>, area: (f32, f32, f32, f32), row_height: f32) -> Self {
+        List {
+            items,
+            area,
+            row_height,
+        }
+    }
+
+    fn visible_rows(&self) -> usize {
+        ((self.area.3 / self.row_height).floor() as usize).max(1)
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.items.len().saturating_sub(self.visible_rows())
+    }
+
+    fn is_button(&self, n: usize) -> bool {
+        matches!(
+            self.items.get(n),
+            Some(Item::Button(..)) | Some(Item::BoardButton { .. })
+        )
+    }
+
+    // The nearest button from `from`, going by steps of `step` rows
+    fn next_button(&self, from: usize, step: isize) -> Option<usize> {
+        let len = self.items.len() as isize;
+        (1..=len)
+            .map(|k| (from as isize + step * k).rem_euclid(len) as usize)
+            .find(|&n| self.is_button(n))
+    }
+
+    // Keep the focus on a button and in view, the list having possibly
+    // changed since the last frame
+    pub fn update(&self, state: &mut ListState) {
+        if self.items.is_empty() {
+            *state = ListState::default();
+            return;
+        }
+        state.focus = state.focus.min(self.items.len() - 1);
+        if !self.is_button(state.focus) {
+            state.focus = self.next_button(state.focus, 1).unwrap_or(state.focus);
+        }
+        self.scroll_to_focus(state);
+    }
+
+    fn scroll_to_focus(&self, state: &mut ListState) {
+        if state.focus < state.scroll {
+            state.scroll = state.focus;
+        } else if state.focus >= state.scroll + self.visible_rows() {
+            state.scroll = state.focus + 1 - self.visible_rows();
+        }
+        state.scroll = state.scroll.min(self.max_scroll());
+    }
+
+    // Rows on screen, with their rectangle in the window
+    fn rows(&self, state: &ListState, layout: &Layout) -> Vec<(usize, (f32, f32, f32, f32))> {
+        let (x, y, width, _) = self.area;
+        (state.scroll..self.items.len().min(state.scroll + self.visible_rows()))
+            .map(|n| {
+                let top = y + (n - state.scroll) as f32 * self.row_height;
+                (n, layout.rect((x, top, width, self.row_height - ROW_GAP)))
+            })
+            .collect()
+    }
+
+    // The button under `position`, if any
+    pub fn button_at(&self, state: &ListState, layout: &Layout, position: Vector) -> Option<usize> {
+        self.rows(state, layout)
+            .into_iter()
+            .find(|(n, (x, y, width, height))| {
+                self.is_button(*n)
+                    && position.x >= *x
+                    && position.x <= x + width
+                    && position.y >= *y
+                    && position.y <= y + height
+            })
+            .map(|(n, _)| n)
+    }
+
+    // Follow the mouse, the wheel and the commands of the keyboard and gamepad,
+    // telling what to do when a button is chosen. `position` is where the
+    // mouse is, in the coordinates of the camera.
+    pub fn handle(
+        &self,
+        state: &mut ListState,
+        layout: &Layout,
+        event: &Event,
+        position: Vector,
+        command: Option<Command>,
+    ) -> Option<A> {
+        match event {
+            Event::PointerMoved(_) => {
+                if let Some(n) = self.button_at(state, layout, position) {
+                    state.focus = n;
+                }
+                None
+            }
+            Event::PointerInput(p_ev)
+                if p_ev.button() == quicksilver::blinds::MouseButton::Left && p_ev.is_down() =>
+            {
+                self.button_at(state, layout, position)
+                    .and_then(|n| self.action(n))
+            }
+            Event::ScrollInput(delta) => {
+                let lines = match delta {
+                    ScrollDelta::Lines(v) => v.y,
+                    ScrollDelta::Pixels(v) => v.y / PIXELS_PER_LINE,
+                };
+                let scroll = state.scroll as f32 - lines.round();
+                state.scroll = (scroll.max(0.) as usize).min(self.max_scroll());
+                // Keep the focus on one of the buttons still in view
+                let mut in_view =
+                    state.scroll..self.items.len().min(state.scroll + self.visible_rows());
+                if !in_view.contains(&state.focus) {
+                    let focus = if state.focus < state.scroll {
+                        in_view.find(|&n| self.is_button(n))
+                    } else {
+                        in_view.rev().find(|&n| self.is_button(n))
+                    };
+                    state.focus = focus.unwrap_or(state.focus);
+                }
+                None
+            }
+            _ => match command {
+                Some(Command::Up) => {
+                    state.focus = self.next_button(state.focus, -1).unwrap_or(state.focus);
+                    self.scroll_to_focus(state);
+                    None
+                }
+                Some(Command::Down) => {
+                    state.focus = self.next_button(state.focus, 1).unwrap_or(state.focus);
+                    self.scroll_to_focus(state);
+                    None
+                }
+                Some(Command::Select) => self.action(state.focus),
+                _ => None,
+            },
+        }
+    }
+
+    fn action(&self, n: usize) -> Option<A> {
+        match self.items.get(n) {
+            Some(Item::Button(_, action)) | Some(Item::BoardButton { action, .. }) => Some(*action),
+            _ => None,
+        }
+    }
+
+    pub fn draw(
+        &self,
+        state: &ListState,
+        gfx: &mut Graphics,
+        font: &mut FontRenderer,
+        layout: &Layout,
+        theme: &Theme,
+    ) -> quicksilver::Result<()> {
+        for (n, rect) in self.rows(state, layout) {
+            match &self.items[n] {
+                Item::Button(label, _) => {
+                    button(gfx, font, layout, rect, label, n == state.focus, theme)?
+                }
+                Item::BoardButton {
+                    label,
+                    details,
+                    board,
+                    ..
+                } => board_button(
+                    gfx,
+                    font,
+                    layout,
+                    rect,
+                    label,
+                    details,
+                    board,
+                    n == state.focus,
+                    theme,
+                )?,
+                Item::Label(text) => {
+                    font.draw(
+                        gfx,
+                        text,
+                        theme.text,
+                        Vector::new(rect.0, rect.1 + layout.len(25.)),
+                    )?;
+                }
+            }
+        }
+
+        // Arrows on the side when some rows are out of view
+        let (x, y, width, _) = self.area;
+        let arrow = |tip: f32, base: f32| {
+            [
+                layout.point(x + width + 20., tip),
+                layout.point(x + width + 12., base),
+                layout.point(x + width + 28., base),
+            ]
+        };
+        if state.scroll > 0 {
+            gfx.fill_polygon(&arrow(y, y + 12.), theme.text);
+        }
+        if state.scroll < self.max_scroll() {
+            let bottom = y + self.visible_rows() as f32 * self.row_height - ROW_GAP;
+            gfx.fill_polygon(&arrow(bottom, bottom - 12.), theme.text);
+        }
+        Ok(())
+    }
+}